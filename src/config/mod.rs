@@ -11,11 +11,35 @@ pub struct SourceConfig {
     pub base_url: Option<String>,
 }
 
+/// `[ingest]` configuration block from config.toml.
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct IngestConfig {
+    /// Extra regexes for pulling a date (and title) out of file names
+    #[serde(default)]
+    pub filename_patterns: Vec<String>,
+    /// Per-path source, tag and format routing
+    #[serde(default)]
+    pub rules: Vec<IngestRule>,
+}
+
+/// One `[[ingest.rules]]` entry. A file matches when it matches every
+/// selector given (`path` glob, `filename` regex).
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct IngestRule {
+    pub path: Option<String>,
+    pub filename: Option<String>,
+    pub source: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub format: Option<String>,
+}
+
 /// Top-level tss config file structure.
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct TssConfig {
     pub fireflies: Option<SourceConfig>,
     pub pocket: Option<SourceConfig>,
+    pub ingest: Option<IngestConfig>,
 }
 
 impl TssConfig {
@@ -52,6 +76,18 @@ impl TssConfig {
             lines.push("[pocket]".to_string());
             display_source_config(&mut lines, pk);
         }
+        if let Some(ref ingest) = self.ingest {
+            lines.push("[ingest]".to_string());
+            if !ingest.filename_patterns.is_empty() {
+                let patterns: Vec<String> =
+                    ingest.filename_patterns.iter().map(|p| format!("'{p}'")).collect();
+                lines.push(format!("  filename_patterns = [{}]", patterns.join(", ")));
+            }
+            for rule in &ingest.rules {
+                lines.push("[[ingest.rules]]".to_string());
+                display_rule(&mut lines, rule);
+            }
+        }
         if lines.is_empty() {
             lines.push("(no sources configured)".to_string());
         }
@@ -79,6 +115,24 @@ fn display_source_config(lines: &mut Vec<String>, sc: &SourceConfig) {
     }
 }
 
+fn display_rule(lines: &mut Vec<String>, rule: &IngestRule) {
+    let fields = [
+        ("path", &rule.path),
+        ("filename", &rule.filename),
+        ("source", &rule.source),
+        ("format", &rule.format),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            lines.push(format!("  {name} = '{value}'"));
+        }
+    }
+    if !rule.tags.is_empty() {
+        let tags: Vec<String> = rule.tags.iter().map(|t| format!("\"{t}\"")).collect();
+        lines.push(format!("  tags = [{}]", tags.join(", ")));
+    }
+}
+
 /// Resolve a credential through the chain: CLI flag > env var > config key > config command.
 pub fn resolve_credential(
     cli_flag: Option<&str>,
@@ -162,6 +216,23 @@ pub fn default_config_template() -> &'static str {
 # api_key = "your-pocket-api-key"
 # api_key_command = "your-secrets-manager-command-here"
 # default_tag = "your-tag-name"
+
+[ingest]
+# Extra file name patterns for meeting dates, tried before the built-in
+# ISO (2025-03-14), compact (20250314_1030) and US (03-14-2025) ones.
+# Use year/month/day[/hour/minute] or date groups, and optionally title.
+# filename_patterns = ['^(?P<title>.+) \((?P<day>\d{2})\.(?P<month>\d{2})\.(?P<year>\d{4})\)$']
+
+# Route files to a source, tags and format by path glob and/or filename regex.
+# Relative globs match anywhere; --source and --format on the command line win.
+# [[ingest.rules]]
+# path = "recordings/zoom/**"
+# source = "zoom"
+# tags = ["customer"]
+#
+# [[ingest.rules]]
+# filename = '\.log$'
+# format = "text"
 "#
 }
 
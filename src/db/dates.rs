@@ -0,0 +1,126 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+/// Canonical stored form: UTC RFC 3339 with second precision, so plain string
+/// comparison orders dates correctly.
+const CANONICAL: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Metadata key holding the date string as it was ingested, when it differs
+/// from the stored canonical value.
+pub const ORIGINAL_DATE_KEY: &str = "original_date";
+
+/// Naive date-time layouts, tried after RFC 3339 / RFC 2822. Values without a
+/// zone are taken as UTC.
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y %I:%M %p",
+];
+
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%Y.%m.%d",
+    "%Y%m%d",
+    "%m/%d/%Y",
+    "%B %d, %Y",
+    "%B %d %Y",
+    "%b %d, %Y",
+    "%b %d %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+];
+
+/// Parse a date in any of the forms sources produce: RFC 3339 with or without
+/// offset, RFC 2822, naive date-times, bare dates, long-form dates
+/// (`January 15, 2026`) and epoch seconds or milliseconds.
+pub fn parse(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    // Offsets without a colon (`+0200`) and a trailing `UTC`
+    if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    let value = value.strip_suffix(" UTC").unwrap_or(value);
+
+    if value.len() >= 9 && value.chars().all(|c| c.is_ascii_digit()) {
+        let n: i64 = value.parse().ok()?;
+        // Eight digits is a compact date (`20260115`), handled below
+        return if value.len() >= 12 {
+            DateTime::from_timestamp_millis(n)
+        } else {
+            DateTime::from_timestamp(n, 0)
+        };
+    }
+
+    for format in DATETIME_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt.and_utc());
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(d) = NaiveDate::parse_from_str(value, format) {
+            return Some(d.and_time(NaiveTime::MIN).and_utc());
+        }
+    }
+
+    None
+}
+
+/// Normalize a date to canonical UTC RFC 3339. Empty stays empty; anything
+/// unparseable becomes empty too, since a free-text date would break range
+/// filters. Callers keep the original string (see `ORIGINAL_DATE_KEY`).
+pub fn normalize(value: &str) -> String {
+    parse(value)
+        .map(|dt| dt.format(CANONICAL).to_string())
+        .unwrap_or_default()
+}
+
+/// Turn a `--from`/`--to` value into a bound comparable with stored dates. A
+/// bare day as the upper bound covers the whole day. Unparseable values are
+/// passed through unchanged.
+pub fn filter_bound(value: &str, end_of_day: bool) -> String {
+    let value = value.trim();
+    if end_of_day {
+        if let Ok(d) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            let end = NaiveTime::from_hms_opt(23, 59, 59).expect("valid time");
+            return d.and_time(end).and_utc().format(CANONICAL).to_string();
+        }
+    }
+    match parse(value) {
+        Some(dt) => dt.format(CANONICAL).to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Normalize `date` and, if that changed it, record the original under
+/// `metadata.original_date`. Metadata that isn't an object is left alone.
+pub fn normalize_with_metadata(
+    date: &str,
+    metadata: Option<&serde_json::Value>,
+) -> (String, Option<serde_json::Value>) {
+    let normalized = normalize(date);
+    let mut metadata = metadata.cloned();
+    if normalized != date {
+        let object = metadata.get_or_insert_with(|| serde_json::json!({}));
+        if let Some(map) = object.as_object_mut() {
+            map.entry(ORIGINAL_DATE_KEY)
+                .or_insert_with(|| serde_json::Value::String(date.to_string()));
+        }
+    }
+    (normalized, metadata)
+}
@@ -2,6 +2,8 @@ use anyhow::Result;
 use rusqlite::Connection;
 use tracing::info;
 
+use super::{dates, schema};
+
 /// Run all pending migrations. Currently a no-op since v1 schema is created
 /// fresh by schema.rs. Future schema changes will be added here as numbered
 /// migrations.
@@ -39,6 +41,59 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         Ok(())
     })?;
 
+    // Per-segment extras such as ASR confidence and word timings
+    run_migration(conn, 2, "add_segment_metadata", |c| {
+        c.execute_batch("ALTER TABLE segments ADD COLUMN metadata TEXT;")?;
+        Ok(())
+    })?;
+
+    // Hash of the ingested file, so unchanged re-ingests can be skipped
+    run_migration(conn, 3, "add_content_hash", |c| {
+        c.execute_batch("ALTER TABLE transcripts ADD COLUMN content_hash TEXT;")?;
+        Ok(())
+    })?;
+
+    // Dates were stored as ingested (bare days, offsets, empty strings)
+    run_migration(conn, 4, "normalize_dates", normalize_dates)?;
+
+    // Batch writes index segments themselves and need the insert trigger to
+    // stand aside while they do
+    run_migration(conn, 5, "deferrable_segment_trigger", |c| {
+        c.execute_batch("DROP TRIGGER IF EXISTS segments_ai;")?;
+        c.execute_batch(&schema::segments_insert_trigger())?;
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Rewrite every transcript date to canonical UTC RFC 3339, keeping the
+/// original in `metadata.original_date`.
+fn normalize_dates(conn: &Connection) -> Result<()> {
+    let rows: Vec<(String, String, Option<String>)> = conn
+        .prepare("SELECT id, date, metadata FROM transcripts")?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = 0;
+    for (id, date, metadata) in rows {
+        let metadata: Option<serde_json::Value> =
+            metadata.and_then(|m| serde_json::from_str(&m).ok());
+        let (normalized, metadata) = dates::normalize_with_metadata(&date, metadata.as_ref());
+        if normalized == date {
+            continue;
+        }
+        let metadata_json = metadata.as_ref().map(serde_json::to_string).transpose()?;
+        tx.execute(
+            "UPDATE transcripts SET date = ?2, metadata = ?3 WHERE id = ?1",
+            rusqlite::params![id, normalized, metadata_json],
+        )?;
+        changed += 1;
+    }
+    tx.commit()?;
+
+    info!("Normalized {changed} transcript dates");
     Ok(())
 }
 
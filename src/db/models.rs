@@ -10,6 +10,7 @@ pub struct Transcript {
     pub summary: String,
     pub raw_text: String,
     pub metadata: Option<serde_json::Value>,
+    pub content_hash: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -30,6 +31,7 @@ pub struct Segment {
     pub start_time: f64,
     pub end_time: f64,
     pub segment_index: i64,
+    pub metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +45,8 @@ pub struct ActionItem {
 /// Data needed to insert a new transcript (no auto-generated fields).
 #[derive(Debug, Clone)]
 pub struct NewTranscript {
+    /// Empty when the source format carries no ID; the ingest layer then
+    /// assigns a stable one derived from the file path or content.
     pub id: String,
     pub title: String,
     pub date: String,
@@ -51,6 +55,8 @@ pub struct NewTranscript {
     pub summary: String,
     pub raw_text: String,
     pub metadata: Option<serde_json::Value>,
+    /// SHA-256 of the ingested file, used to skip unchanged re-ingests.
+    pub content_hash: Option<String>,
     pub speakers: Vec<String>,
     pub segments: Vec<NewSegment>,
     pub tags: Vec<String>,
@@ -65,6 +71,7 @@ pub struct NewSegment {
     pub start_time: f64,
     pub end_time: f64,
     pub segment_index: i64,
+    pub metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +80,15 @@ pub struct NewActionItem {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Result of `Database::upsert_transcript`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
 /// Stats returned by `tss stats`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbStats {
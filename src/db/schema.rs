@@ -1,6 +1,23 @@
 use anyhow::Result;
 use rusqlite::Connection;
 
+/// `tss_meta` key that batch writes set inside their transaction while they
+/// index segments themselves (see `Database::write_batch`).
+pub const DEFER_SEGMENT_FTS: &str = "defer_segment_fts";
+
+/// The trigger that keeps `segments_fts` in sync on insert, unless a batch
+/// write has set `DEFER_SEGMENT_FTS`.
+pub fn segments_insert_trigger() -> String {
+    format!(
+        "
+    CREATE TRIGGER IF NOT EXISTS segments_ai AFTER INSERT ON segments
+    WHEN NOT EXISTS (SELECT 1 FROM tss_meta WHERE key = '{DEFER_SEGMENT_FTS}') BEGIN
+        INSERT INTO segments_fts(rowid, text, speaker)
+        VALUES (new.rowid, new.text, new.speaker);
+    END;"
+    )
+}
+
 pub fn create_schema(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "
@@ -106,12 +123,6 @@ pub fn create_schema(conn: &Connection) -> Result<()> {
             VALUES (new.rowid, new.title, new.summary, new.raw_text);
         END;
 
-        -- segments insert
-        CREATE TRIGGER IF NOT EXISTS segments_ai AFTER INSERT ON segments BEGIN
-            INSERT INTO segments_fts(rowid, text, speaker)
-            VALUES (new.rowid, new.text, new.speaker);
-        END;
-
         -- segments delete
         CREATE TRIGGER IF NOT EXISTS segments_ad AFTER DELETE ON segments BEGIN
             INSERT INTO segments_fts(segments_fts, rowid, text, speaker)
@@ -128,6 +139,9 @@ pub fn create_schema(conn: &Connection) -> Result<()> {
         ",
     )?;
 
+    // segments insert
+    conn.execute_batch(&segments_insert_trigger())?;
+
     // Set schema version
     conn.execute(
         "INSERT OR REPLACE INTO tss_meta (key, value) VALUES ('schema_version', '1')",
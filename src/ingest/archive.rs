@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Archive containers that can be ingested directly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Detect an archive from its file name (`.zip`, `.tar`, `.tar.gz`, `.tgz`).
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Largest entry that will be read out of an archive. Transcripts are far
+/// smaller; anything bigger is reported as a failed entry rather than
+/// decompressed into memory.
+pub const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A regular file inside an archive.
+pub struct ArchiveEntry {
+    /// Path of the entry within the archive
+    pub name: String,
+    /// Raw contents, or why they couldn't be read
+    pub content: Result<Vec<u8>>,
+}
+
+/// Visit the file entries of an archive whose names pass `wanted`, one at a
+/// time. Zip entries come sorted by name; tar entries in archive order, since
+/// a tar stream can only be read front to back. Directories, hidden files and
+/// macOS `__MACOSX` metadata are skipped, and entries over `MAX_ENTRY_SIZE`
+/// come back as errors.
+pub fn read_entries(
+    path: &Path,
+    kind: ArchiveKind,
+    wanted: impl Fn(&str) -> bool,
+    mut visit: impl FnMut(ArchiveEntry) -> Result<()>,
+) -> Result<()> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open archive: {}", path.display()))?;
+    let reader = BufReader::new(file);
+
+    match kind {
+        ArchiveKind::Zip => read_zip(reader, &wanted, &mut visit),
+        ArchiveKind::Tar => read_tar(reader, &wanted, &mut visit),
+        ArchiveKind::TarGz => read_tar(flate2::read::GzDecoder::new(reader), &wanted, &mut visit),
+    }
+    .with_context(|| format!("Failed to read archive: {}", path.display()))
+}
+
+fn read_zip(
+    reader: BufReader<File>,
+    wanted: &impl Fn(&str) -> bool,
+    visit: &mut impl FnMut(ArchiveEntry) -> Result<()>,
+) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+
+    let mut names = Vec::new();
+    for i in 0..zip.len() {
+        let file = zip.by_index_raw(i)?;
+        if file.is_file() && !is_hidden(file.name()) && wanted(file.name()) {
+            names.push((file.name().to_string(), i));
+        }
+    }
+    names.sort();
+
+    for (name, i) in names {
+        let mut file = zip.by_index(i)?;
+        let size = file.size();
+        visit(ArchiveEntry {
+            content: read_bytes(&mut file, size, &name),
+            name,
+        })?;
+    }
+
+    Ok(())
+}
+
+fn read_tar<R: Read>(
+    reader: R,
+    wanted: &impl Fn(&str) -> bool,
+    visit: &mut impl FnMut(ArchiveEntry) -> Result<()>,
+) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let name = name.trim_start_matches("./").to_string();
+        if is_hidden(&name) || !wanted(&name) {
+            continue;
+        }
+        let size = entry.size();
+        visit(ArchiveEntry {
+            content: read_bytes(&mut entry, size, &name),
+            name,
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Read an entry, refusing it up front if its declared size is over the
+/// limit and again if it inflates past the limit anyway.
+fn read_bytes(reader: &mut impl Read, declared: u64, name: &str) -> Result<Vec<u8>> {
+    let too_large = || {
+        anyhow::anyhow!(
+            "Archive entry {name} is larger than {} MiB",
+            MAX_ENTRY_SIZE / (1024 * 1024)
+        )
+    };
+    if declared > MAX_ENTRY_SIZE {
+        return Err(too_large());
+    }
+
+    let mut content = Vec::with_capacity(declared as usize);
+    reader
+        .take(MAX_ENTRY_SIZE + 1)
+        .read_to_end(&mut content)
+        .with_context(|| format!("Failed to read archive entry: {name}"))?;
+    if content.len() as u64 > MAX_ENTRY_SIZE {
+        return Err(too_large());
+    }
+    Ok(content)
+}
+
+/// Dotfiles (including AppleDouble `._name` forks) and `__MACOSX/` entries.
+fn is_hidden(name: &str) -> bool {
+    name.split('/')
+        .any(|part| part.starts_with('.') || part == "__MACOSX")
+}
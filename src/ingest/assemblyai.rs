@@ -0,0 +1,222 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::filename_to_title;
+use crate::ingest::whisper::word_timing;
+
+/// AssemblyAI transcript JSON (`GET /v2/transcript/{id}` response saved to disk).
+/// All times are in milliseconds except `audio_duration` (seconds).
+#[derive(Debug, Deserialize)]
+struct AssemblyAiTranscript {
+    id: Option<String>,
+    text: Option<String>,
+    audio_url: Option<String>,
+    audio_duration: Option<f64>,
+    language_code: Option<String>,
+    confidence: Option<f64>,
+    utterances: Option<Vec<AssemblyAiUtterance>>,
+    words: Option<Vec<AssemblyAiWord>>,
+    summary: Option<String>,
+    chapters: Option<Vec<AssemblyAiChapter>>,
+    auto_highlights_result: Option<AssemblyAiHighlights>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAiUtterance {
+    speaker: Option<String>,
+    text: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+    confidence: Option<f64>,
+    words: Option<Vec<AssemblyAiWord>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAiWord {
+    text: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+    confidence: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAiChapter {
+    headline: Option<String>,
+    gist: Option<String>,
+    summary: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAiHighlights {
+    results: Option<Vec<AssemblyAiHighlight>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssemblyAiHighlight {
+    text: Option<String>,
+}
+
+/// True if a parsed JSON document looks like an AssemblyAI transcript.
+pub fn is_assemblyai(value: &serde_json::Value) -> bool {
+    value.get("utterances").is_some()
+        || (value.get("audio_duration").is_some() && value.get("language_code").is_some())
+}
+
+/// Convert AssemblyAI JSON into a NewTranscript. Utterances become segments;
+/// without diarization the whole `text` is stored as one segment.
+pub fn parse_assemblyai(
+    value: serde_json::Value,
+    filename: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    let at: AssemblyAiTranscript =
+        serde_json::from_value(value).context("Failed to parse AssemblyAI transcript")?;
+
+    let mut speakers = Vec::new();
+    let mut seen = HashSet::new();
+    let mut raw_lines = Vec::new();
+    let mut segments = Vec::new();
+
+    for (i, u) in at.utterances.unwrap_or_default().into_iter().enumerate() {
+        let speaker = u
+            .speaker
+            .map(|s| format!("Speaker {s}"))
+            .unwrap_or_default();
+        let text = u.text.unwrap_or_default();
+        if !speaker.is_empty() && seen.insert(speaker.clone()) {
+            speakers.push(speaker.clone());
+        }
+        raw_lines.push(format!("{speaker}: {text}"));
+
+        let mut meta = serde_json::Map::new();
+        if let Some(c) = u.confidence {
+            meta.insert("confidence".into(), serde_json::json!(c));
+        }
+        let words = words_metadata(u.words.unwrap_or_default());
+        if !words.is_empty() {
+            meta.insert("words".into(), serde_json::Value::Array(words));
+        }
+
+        segments.push(NewSegment {
+            speaker,
+            text,
+            start_time: ms(u.start),
+            end_time: ms(u.end),
+            segment_index: i as i64,
+            metadata: if meta.is_empty() {
+                None
+            } else {
+                Some(serde_json::Value::Object(meta))
+            },
+        });
+    }
+
+    let full_text = at.text.unwrap_or_default();
+    if segments.is_empty() && !full_text.trim().is_empty() {
+        let words = at.words.unwrap_or_default();
+        let start_time = ms(words.first().and_then(|w| w.start));
+        let end_time = ms(words.last().and_then(|w| w.end));
+        let words = words_metadata(words);
+        segments.push(NewSegment {
+            speaker: String::new(),
+            text: full_text.trim().to_string(),
+            start_time,
+            end_time,
+            segment_index: 0,
+            metadata: if words.is_empty() {
+                None
+            } else {
+                Some(serde_json::json!({ "words": words }))
+            },
+        });
+    }
+
+    let raw_text = if raw_lines.is_empty() {
+        full_text.trim().to_string()
+    } else {
+        raw_lines.join("\n")
+    };
+
+    let duration_seconds = at
+        .audio_duration
+        .unwrap_or_else(|| segments.iter().map(|s| s.end_time).fold(0.0, f64::max));
+
+    let keywords: Vec<String> = at
+        .auto_highlights_result
+        .and_then(|h| h.results)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|h| h.text)
+        .collect();
+
+    let mut meta = serde_json::Map::new();
+    meta.insert("asr".into(), serde_json::Value::String("assemblyai".into()));
+    if let Some(v) = at.language_code {
+        meta.insert("language".into(), serde_json::Value::String(v));
+    }
+    if let Some(v) = at.confidence {
+        meta.insert("confidence".into(), serde_json::json!(v));
+    }
+    if let Some(v) = at.audio_url {
+        meta.insert("audio_url".into(), serde_json::Value::String(v));
+    }
+    let chapters: Vec<serde_json::Value> = at
+        .chapters
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| {
+            serde_json::json!({
+                "headline": c.headline.unwrap_or_default(),
+                "gist": c.gist.unwrap_or_default(),
+                "summary": c.summary.unwrap_or_default(),
+                "start": ms(c.start),
+                "end": ms(c.end),
+            })
+        })
+        .collect();
+    if !chapters.is_empty() {
+        meta.insert("chapters".into(), serde_json::Value::Array(chapters));
+    }
+
+    Ok(NewTranscript {
+        id: at.id.unwrap_or_default(),
+        title: filename_to_title(filename),
+        date: String::new(),
+        duration_seconds,
+        source: default_source.unwrap_or("assemblyai").to_string(),
+        summary: at.summary.unwrap_or_default(),
+        raw_text,
+        metadata: Some(serde_json::Value::Object(meta)),
+        content_hash: None,
+        speakers,
+        segments,
+        tags: Vec::new(),
+        keywords,
+        action_items: Vec::new(),
+    })
+}
+
+fn words_metadata(words: Vec<AssemblyAiWord>) -> Vec<serde_json::Value> {
+    words
+        .into_iter()
+        .filter_map(|w| {
+            let word = w.text?;
+            Some(word_timing(
+                &word,
+                w.start.map(|v| v / 1000.0),
+                w.end.map(|v| v / 1000.0),
+                "confidence",
+                w.confidence,
+            ))
+        })
+        .collect()
+}
+
+/// Milliseconds → seconds.
+fn ms(v: Option<f64>) -> f64 {
+    v.unwrap_or(0.0) / 1000.0
+}
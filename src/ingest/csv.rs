@@ -0,0 +1,309 @@
+use anyhow::{bail, Context, Result};
+use std::collections::{HashMap, HashSet};
+
+use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::{filename_to_title, parse_timestamp};
+use crate::ingest::ParsedRecord;
+
+/// A column a segment table can map onto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    TranscriptId,
+    Title,
+    Date,
+    Speaker,
+    Start,
+    End,
+    Text,
+}
+
+impl Field {
+    /// Recognise a field from a header cell or `--columns` name.
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace([' ', '-'], "_");
+        Some(match name.as_str() {
+            "transcript_id" | "transcriptid" | "meeting_id" | "call_id" | "conversation_id"
+            | "recording_id" => Field::TranscriptId,
+            "title" | "meeting" | "meeting_title" => Field::Title,
+            "date" | "meeting_date" | "created_at" => Field::Date,
+            "speaker" | "speaker_name" | "name" | "participant" | "speaker_label" => Field::Speaker,
+            "start" | "start_time" | "begin" | "from" | "timestamp" | "time" | "start_seconds" => {
+                Field::Start
+            }
+            "end" | "end_time" | "stop" | "to" | "end_seconds" => Field::End,
+            "text" | "transcript" | "utterance" | "content" | "sentence" | "message" | "words" => {
+                Field::Text
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Column mapping from `--columns`. Each comma-separated entry is either
+/// `field=Header` (match a header cell by name) or a bare `field`, which maps
+/// to the column at that entry's position: `speaker,start,end,text`.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnMap {
+    entries: Vec<(Field, Option<String>)>,
+}
+
+impl ColumnMap {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut entries = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (field, header) = match part.split_once('=') {
+                Some((f, h)) => (f, Some(h.trim().to_string())),
+                None => (part, None),
+            };
+            let Some(field) = Field::from_name(field) else {
+                bail!(
+                    "Unknown column '{field}'. Use: transcript_id, title, date, speaker, start, end, text"
+                );
+            };
+            entries.push((field, header));
+        }
+        if entries.is_empty() {
+            bail!("--columns is empty");
+        }
+        Ok(ColumnMap { entries })
+    }
+
+    fn has_names(&self) -> bool {
+        self.entries.iter().any(|(_, name)| name.is_some())
+    }
+}
+
+/// Resolved column indexes for one file.
+#[derive(Debug, Default)]
+struct Columns {
+    indexes: HashMap<Field, usize>,
+}
+
+impl Columns {
+    fn get<'a>(&self, row: &'a ::csv::StringRecord, field: Field) -> Option<&'a str> {
+        let i = *self.indexes.get(&field)?;
+        row.get(i).map(str::trim).filter(|v| !v.is_empty())
+    }
+}
+
+/// Parse a CSV (or TSV, with `delimiter = b'\t'`) segment table. Rows become
+/// segments; with a `transcript_id` column each distinct value becomes its own
+/// transcript, otherwise the whole file is one transcript.
+///
+/// `transcript_id` values are only unique within one export (many tools
+/// number meetings from 1), so they key the record within the file rather
+/// than becoming the stored ID, and are kept in `metadata.transcript_id`.
+///
+/// A header row is detected by known column names (`speaker`, `start`, `end`,
+/// `text`, ...). Headerless files use `--columns`, or a layout guessed from
+/// the column count. Times may be seconds (`83.5`) or `HH:MM:SS(.mmm)`.
+/// Without a `date` column, transcripts are dated `date` (the file's mtime).
+pub fn parse_csv(
+    content: &str,
+    filename: &str,
+    date: &str,
+    delimiter: u8,
+    columns: Option<&ColumnMap>,
+    default_source: Option<&str>,
+) -> Result<Vec<ParsedRecord>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+    let mut rows = Vec::new();
+    for (i, row) in reader.records().enumerate() {
+        let row = row.with_context(|| format!("Failed to read row {} of {filename}", i + 1))?;
+        if row.iter().any(|c| !c.trim().is_empty()) {
+            rows.push(row);
+        }
+    }
+    let Some(first) = rows.first() else {
+        bail!("No rows in {filename}");
+    };
+
+    // Named `--columns` entries always refer to a header row
+    let named = columns.is_some_and(ColumnMap::has_names);
+    let header = (named || looks_like_header(first)).then(|| first.clone());
+    let cols = resolve_columns(header.as_ref(), first.len(), columns)
+        .with_context(|| format!("Cannot map columns in {filename}"))?;
+    let body = &rows[usize::from(header.is_some())..];
+
+    // Group rows by transcript_id, keeping first-seen order
+    let mut order: Vec<Option<String>> = Vec::new();
+    let mut groups: HashMap<Option<String>, Vec<&::csv::StringRecord>> = HashMap::new();
+    for row in body {
+        let key = cols.get(row, Field::TranscriptId).map(str::to_string);
+        if !groups.contains_key(&key) {
+            order.push(key.clone());
+        }
+        groups.entry(key).or_default().push(row);
+    }
+
+    let source = default_source.unwrap_or("csv");
+    Ok(order
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let rows = &groups[&key];
+            let raw = rows
+                .iter()
+                .map(|r| r.iter().collect::<Vec<_>>().join("\u{1f}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ParsedRecord {
+                number: i + 1,
+                raw,
+                key: key.clone(),
+                transcript: build_transcript(rows, &cols, key, filename, date, source),
+            }
+        })
+        .collect())
+}
+
+fn looks_like_header(row: &::csv::StringRecord) -> bool {
+    row.iter().any(|c| Field::from_name(c).is_some())
+}
+
+fn resolve_columns(
+    header: Option<&::csv::StringRecord>,
+    width: usize,
+    map: Option<&ColumnMap>,
+) -> Result<Columns> {
+    let mut cols = Columns::default();
+
+    if let Some(map) = map {
+        for (pos, (field, name)) in map.entries.iter().enumerate() {
+            let index = match name {
+                Some(name) => header
+                    .and_then(|h| h.iter().position(|c| c.trim().eq_ignore_ascii_case(name)))
+                    .with_context(|| format!("No column named '{name}' in header"))?,
+                None => pos,
+            };
+            cols.indexes.insert(*field, index);
+        }
+    } else if let Some(header) = header {
+        for (i, cell) in header.iter().enumerate() {
+            if let Some(field) = Field::from_name(cell) {
+                cols.indexes.entry(field).or_insert(i);
+            }
+        }
+    } else {
+        let layout: &[Field] = match width {
+            1 => &[Field::Text],
+            2 => &[Field::Speaker, Field::Text],
+            3 => &[Field::Speaker, Field::Start, Field::Text],
+            _ => &[Field::Speaker, Field::Start, Field::End, Field::Text],
+        };
+        for (i, field) in layout.iter().enumerate() {
+            cols.indexes.insert(*field, i);
+        }
+    }
+
+    if !cols.indexes.contains_key(&Field::Text) {
+        bail!("no text column (use --columns to map one)");
+    }
+    Ok(cols)
+}
+
+fn build_transcript(
+    rows: &[&::csv::StringRecord],
+    cols: &Columns,
+    transcript_id: Option<String>,
+    filename: &str,
+    file_date: &str,
+    source: &str,
+) -> Result<NewTranscript> {
+    let mut segments: Vec<NewSegment> = Vec::new();
+    let mut speakers = Vec::new();
+    let mut seen = HashSet::new();
+    let mut raw_lines = Vec::new();
+    let mut explicit_end = Vec::new();
+
+    for row in rows {
+        let Some(text) = cols.get(row, Field::Text) else {
+            continue;
+        };
+        let speaker = cols.get(row, Field::Speaker).unwrap_or_default().to_string();
+        let start_time = cols.get(row, Field::Start).map(parse_time).transpose()?;
+        let end_time = cols.get(row, Field::End).map(parse_time).transpose()?;
+
+        if !speaker.is_empty() && seen.insert(speaker.clone()) {
+            speakers.push(speaker.clone());
+        }
+        if speaker.is_empty() {
+            raw_lines.push(text.to_string());
+        } else {
+            raw_lines.push(format!("{speaker}: {text}"));
+        }
+        explicit_end.push(end_time.is_some());
+        let start_time = start_time.unwrap_or(0.0);
+        segments.push(NewSegment {
+            speaker,
+            text: text.to_string(),
+            start_time,
+            end_time: end_time.unwrap_or(start_time),
+            segment_index: segments.len() as i64,
+            metadata: None,
+        });
+    }
+
+    // Without an end column, a segment runs until the next one starts
+    for i in 0..segments.len().saturating_sub(1) {
+        if !explicit_end[i] {
+            segments[i].end_time = segments[i + 1].start_time.max(segments[i].start_time);
+        }
+    }
+
+    let first = rows.first();
+    let title = first
+        .and_then(|r| cols.get(r, Field::Title))
+        .map(str::to_string)
+        .unwrap_or_else(|| match &transcript_id {
+            Some(id) => format!("{} {id}", filename_to_title(filename)),
+            None => filename_to_title(filename),
+        });
+    let date = first
+        .and_then(|r| cols.get(r, Field::Date))
+        .unwrap_or(file_date)
+        .to_string();
+    let duration_seconds = segments.iter().map(|s| s.end_time).fold(0.0, f64::max);
+
+    let metadata = transcript_id
+        .as_ref()
+        .map(|id| serde_json::json!({ "transcript_id": id }));
+
+    Ok(NewTranscript {
+        id: String::new(),
+        title,
+        date,
+        duration_seconds,
+        source: source.to_string(),
+        summary: String::new(),
+        raw_text: raw_lines.join("\n"),
+        metadata,
+        content_hash: None,
+        speakers,
+        segments,
+        tags: Vec::new(),
+        keywords: Vec::new(),
+        action_items: Vec::new(),
+    })
+}
+
+/// Seconds (`83.5`) or a clock time (`01:23`, `00:01:23.500`, `00:01:23,500`).
+fn parse_time(value: &str) -> Result<f64> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return Ok(secs);
+    }
+    let value = value.replace(',', ".");
+    if value.contains(':')
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ':' || c == '.')
+    {
+        return Ok(parse_timestamp(&value));
+    }
+    bail!("Invalid time value: {value}")
+}
@@ -0,0 +1,303 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::filename_to_title;
+use crate::ingest::whisper::word_timing;
+
+/// Silence (seconds) that starts a new segment when words carry no speaker change.
+const PAUSE_BREAK: f64 = 2.0;
+
+/// Deepgram pre-recorded response JSON saved to disk. Times are in seconds.
+#[derive(Debug, Deserialize)]
+struct DeepgramResponse {
+    metadata: Option<DeepgramMetadata>,
+    results: DeepgramResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramMetadata {
+    request_id: Option<String>,
+    created: Option<String>,
+    duration: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramResults {
+    channels: Vec<DeepgramChannel>,
+    utterances: Option<Vec<DeepgramUtterance>>,
+    summary: Option<DeepgramSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramChannel {
+    alternatives: Vec<DeepgramAlternative>,
+    detected_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramAlternative {
+    transcript: Option<String>,
+    confidence: Option<f64>,
+    words: Option<Vec<DeepgramWord>>,
+    /// Summarize v1 puts summaries on the alternative
+    summaries: Option<Vec<DeepgramSummaryV1>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramUtterance {
+    channel: Option<usize>,
+    start: Option<f64>,
+    end: Option<f64>,
+    confidence: Option<f64>,
+    transcript: Option<String>,
+    speaker: Option<i64>,
+    words: Option<Vec<DeepgramWord>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DeepgramWord {
+    word: Option<String>,
+    punctuated_word: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+    confidence: Option<f64>,
+    speaker: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramSummary {
+    short: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramSummaryV1 {
+    summary: Option<String>,
+}
+
+/// True if a parsed JSON document looks like a Deepgram response.
+pub fn is_deepgram(value: &serde_json::Value) -> bool {
+    value
+        .get("results")
+        .and_then(|r| r.get("channels"))
+        .is_some_and(|c| c.is_array())
+}
+
+/// Convert Deepgram JSON into a NewTranscript. Uses `results.utterances` when
+/// present, otherwise groups each channel's diarized words by speaker.
+/// Multichannel responses are merged in start-time order, with the channel
+/// standing in for the speaker when there is no diarization.
+pub fn parse_deepgram(
+    value: serde_json::Value,
+    filename: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    let dg: DeepgramResponse =
+        serde_json::from_value(value).context("Failed to parse Deepgram response")?;
+
+    let multichannel = dg.results.channels.len() > 1;
+    let language = dg
+        .results
+        .channels
+        .first()
+        .and_then(|c| c.detected_language.clone());
+    // Each channel's best alternative, keyed by the channel's own index so a
+    // channel without alternatives doesn't renumber the ones after it
+    let alternatives: Vec<(usize, DeepgramAlternative)> = dg
+        .results
+        .channels
+        .into_iter()
+        .enumerate()
+        .filter_map(|(channel, c)| Some((channel, c.alternatives.into_iter().next()?)))
+        .collect();
+    let alternative = alternatives.first().map(|(_, a)| a);
+
+    let mut turns: Vec<Turn> = match dg.results.utterances {
+        Some(utterances) if !utterances.is_empty() => utterances
+            .into_iter()
+            .map(|u| Turn {
+                channel: u.channel.unwrap_or(0),
+                speaker: u.speaker,
+                text: u.transcript.unwrap_or_default(),
+                start: u.start.unwrap_or(0.0),
+                end: u.end.unwrap_or(0.0),
+                confidence: u.confidence,
+                words: u.words.unwrap_or_default(),
+            })
+            .collect(),
+        _ => alternatives
+            .iter()
+            .flat_map(|(channel, a)| group_words(*channel, a.words.as_deref().unwrap_or_default()))
+            .collect(),
+    };
+    turns.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let mut speakers = Vec::new();
+    let mut seen = HashSet::new();
+    let mut raw_lines = Vec::new();
+    let mut segments = Vec::new();
+
+    for (i, turn) in turns.into_iter().enumerate() {
+        let speaker = match (turn.speaker, multichannel) {
+            (Some(s), false) => format!("Speaker {s}"),
+            // Diarization numbers speakers per channel
+            (Some(s), true) => format!("Channel {} Speaker {s}", turn.channel),
+            (None, true) => format!("Channel {}", turn.channel),
+            (None, false) => String::new(),
+        };
+        if !speaker.is_empty() && seen.insert(speaker.clone()) {
+            speakers.push(speaker.clone());
+        }
+        if speaker.is_empty() {
+            raw_lines.push(turn.text.clone());
+        } else {
+            raw_lines.push(format!("{speaker}: {}", turn.text));
+        }
+
+        let mut meta = serde_json::Map::new();
+        if let Some(c) = turn.confidence {
+            meta.insert("confidence".into(), serde_json::json!(c));
+        }
+        let words: Vec<serde_json::Value> = turn
+            .words
+            .iter()
+            .filter_map(|w| {
+                let word = w.punctuated_word.as_ref().or(w.word.as_ref())?;
+                Some(word_timing(word, w.start, w.end, "confidence", w.confidence))
+            })
+            .collect();
+        if !words.is_empty() {
+            meta.insert("words".into(), serde_json::Value::Array(words));
+        }
+
+        segments.push(NewSegment {
+            speaker,
+            text: turn.text,
+            start_time: turn.start,
+            end_time: turn.end,
+            segment_index: i as i64,
+            metadata: if meta.is_empty() {
+                None
+            } else {
+                Some(serde_json::Value::Object(meta))
+            },
+        });
+    }
+
+    let transcript_text: Vec<&str> = alternatives
+        .iter()
+        .filter_map(|(_, a)| a.transcript.as_deref())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    let raw_text = if raw_lines.is_empty() {
+        transcript_text.join("\n")
+    } else {
+        raw_lines.join("\n")
+    };
+
+    let summary = dg
+        .results
+        .summary
+        .and_then(|s| s.short)
+        .or_else(|| {
+            alternative.and_then(|a| {
+                let parts: Vec<String> = a
+                    .summaries
+                    .as_ref()?
+                    .iter()
+                    .filter_map(|s| s.summary.clone())
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join("\n\n"))
+            })
+        })
+        .unwrap_or_default();
+
+    let metadata = dg.metadata;
+    let duration_seconds = metadata
+        .as_ref()
+        .and_then(|m| m.duration)
+        .unwrap_or_else(|| segments.iter().map(|s| s.end_time).fold(0.0, f64::max));
+
+    let mut meta = serde_json::Map::new();
+    meta.insert("asr".into(), serde_json::Value::String("deepgram".into()));
+    if let Some(v) = language {
+        meta.insert("language".into(), serde_json::Value::String(v));
+    }
+    if let Some(v) = alternative.and_then(|a| a.confidence) {
+        meta.insert("confidence".into(), serde_json::json!(v));
+    }
+
+    Ok(NewTranscript {
+        id: metadata
+            .as_ref()
+            .and_then(|m| m.request_id.clone())
+            .unwrap_or_default(),
+        title: filename_to_title(filename),
+        date: metadata.and_then(|m| m.created).unwrap_or_default(),
+        duration_seconds,
+        source: default_source.unwrap_or("deepgram").to_string(),
+        summary,
+        raw_text,
+        metadata: Some(serde_json::Value::Object(meta)),
+        content_hash: None,
+        speakers,
+        segments,
+        tags: Vec::new(),
+        keywords: Vec::new(),
+        action_items: Vec::new(),
+    })
+}
+
+struct Turn {
+    channel: usize,
+    speaker: Option<i64>,
+    text: String,
+    start: f64,
+    end: f64,
+    confidence: Option<f64>,
+    words: Vec<DeepgramWord>,
+}
+
+/// Group one channel's consecutive words into turns, breaking on speaker
+/// change or a long pause.
+fn group_words(channel: usize, words: &[DeepgramWord]) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+
+    for w in words {
+        let start = w.start.unwrap_or(0.0);
+        let end = w.end.unwrap_or(start);
+        let token = w
+            .punctuated_word
+            .as_deref()
+            .or(w.word.as_deref())
+            .unwrap_or_default();
+
+        let continues = turns
+            .last()
+            .is_some_and(|t| t.speaker == w.speaker && start - t.end < PAUSE_BREAK);
+
+        if !continues {
+            turns.push(Turn {
+                channel,
+                speaker: w.speaker,
+                text: String::new(),
+                start,
+                end,
+                confidence: None,
+                words: Vec::new(),
+            });
+        }
+
+        let turn = turns.last_mut().expect("turn pushed above");
+        if !turn.text.is_empty() {
+            turn.text.push(' ');
+        }
+        turn.text.push_str(token);
+        turn.end = end;
+        turn.words.push(w.clone());
+    }
+
+    turns
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{Cursor, Read};
+
+use crate::db::models::NewTranscript;
+use crate::ingest::markdown::filename_to_title;
+use crate::ingest::text::text_to_transcript;
+
+/// Document properties shared by the DOCX and PDF readers.
+#[derive(Debug, Default)]
+pub(crate) struct DocumentProperties {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub created: Option<String>,
+}
+
+impl DocumentProperties {
+    /// Transcript metadata for the properties that don't map onto a column.
+    pub(crate) fn metadata(&self, format: &str) -> serde_json::Value {
+        let mut meta = serde_json::Map::new();
+        meta.insert("document_format".into(), serde_json::Value::String(format.into()));
+        if let Some(author) = &self.author {
+            meta.insert("author".into(), serde_json::Value::String(author.clone()));
+        }
+        serde_json::Value::Object(meta)
+    }
+}
+
+/// Parse a Word document. Paragraph text from `word/document.xml` goes
+/// through the plain-text speaker detection; title, author and created date
+/// come from `docProps/core.xml`.
+pub fn parse_docx(bytes: &[u8], filename: &str, default_source: Option<&str>) -> Result<NewTranscript> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(bytes))
+        .with_context(|| format!("Not a DOCX file: {filename}"))?;
+
+    let document = read_part(&mut zip, "word/document.xml")?
+        .with_context(|| format!("DOCX has no word/document.xml: {filename}"))?;
+    let text = document_text(&document)
+        .with_context(|| format!("Failed to read DOCX body: {filename}"))?;
+
+    let props = match read_part(&mut zip, "docProps/core.xml")? {
+        Some(core) => core_properties(&core).unwrap_or_default(),
+        None => DocumentProperties::default(),
+    };
+
+    Ok(text_to_transcript(
+        &text,
+        props.title.clone().unwrap_or_else(|| filename_to_title(filename)),
+        props.created.clone().unwrap_or_default(),
+        default_source.unwrap_or("docx"),
+        Some(props.metadata("docx")),
+    ))
+}
+
+fn read_part(zip: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Option<String>> {
+    let mut file = match zip.by_name(name) {
+        Ok(f) => f,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open DOCX part: {name}")),
+    };
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)
+        .with_context(|| format!("Failed to read DOCX part: {name}"))?;
+    Ok(Some(xml))
+}
+
+/// Collect `<w:t>` runs, one line per `<w:p>` paragraph. Tabs and breaks are
+/// kept so `Name<tab>0:03` headings still line up for speaker detection.
+fn document_text(xml: &str) -> Result<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_run_text = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.name().as_ref() == b"w:t" => in_run_text = true,
+            Event::End(e) => match e.name().as_ref() {
+                b"w:t" => in_run_text = false,
+                b"w:p" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"w:tab" => text.push('\t'),
+                b"w:br" | b"w:cr" => text.push('\n'),
+                _ => {}
+            },
+            Event::Text(t) if in_run_text => text.push_str(&t.unescape()?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(text)
+}
+
+/// Read `dc:title`, `dc:creator` and `dcterms:created` from core properties.
+fn core_properties(xml: &str) -> Result<DocumentProperties> {
+    let mut reader = Reader::from_str(xml);
+    let mut props = DocumentProperties::default();
+    let mut current: Option<Vec<u8>> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => current = Some(e.name().as_ref().to_vec()),
+            Event::End(_) => current = None,
+            Event::Text(t) => {
+                let value = t.unescape()?.trim().to_string();
+                if value.is_empty() {
+                    continue;
+                }
+                match current.as_deref() {
+                    Some(b"dc:title") => props.title = Some(value),
+                    Some(b"dc:creator") => props.author = Some(value),
+                    Some(b"dcterms:created") => props.created = Some(value),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(props)
+}
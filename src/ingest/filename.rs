@@ -0,0 +1,118 @@
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use regex::{Captures, Regex};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::db::dates;
+
+/// Built-in filename date layouts, tried after any configured patterns.
+/// Each captures the whole date/time run as `stamp`.
+static BUILTIN: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        // ISO: 2025-03-14, 2025-03-14 10.30, 2025-03-14T10:30:00
+        r"(?P<stamp>(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})(?:[ T_]+(?P<hour>[0-2]\d)[:.h-]?(?P<minute>[0-5]\d)(?:[:.-]?(?P<second>[0-5]\d))?)?)(?:[^0-9]|$)",
+        // Compact: 20250314, 20250314_1030, 20250314-103000
+        r"(?:^|[^0-9])(?P<stamp>(?P<year>(?:19|20)\d{2})(?P<month>[01]\d)(?P<day>[0-3]\d)(?:[ T_-]?(?P<hour>[0-2]\d)(?P<minute>[0-5]\d)(?P<second>[0-5]\d)?)?)(?:[^0-9]|$)",
+        // US: 3-14-2025, 03/14/2025, 3.14.2025
+        r"(?:^|[^0-9])(?P<stamp>(?P<month>\d{1,2})[-./](?P<day>\d{1,2})[-./](?P<year>\d{4}))(?:[^0-9]|$)",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).expect("valid built-in filename pattern"))
+    .collect()
+});
+
+/// User-defined filename patterns from `[ingest] filename_patterns`.
+///
+/// A pattern is a regex matched against the file name without extension. It
+/// names its date with `year`/`month`/`day` (plus optional `hour`, `minute`,
+/// `second`) groups or a single `date` group in any format `tss` accepts, and
+/// may capture the meeting name as `title`. Without a `title` group the title
+/// is what's left of the name once the match is removed.
+#[derive(Debug, Clone, Default)]
+pub struct FilenamePatterns {
+    custom: Vec<Regex>,
+}
+
+impl FilenamePatterns {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut custom = Vec::new();
+        for pattern in patterns {
+            let re = Regex::new(pattern)
+                .with_context(|| format!("Invalid filename pattern: {pattern}"))?;
+            let names: Vec<&str> = re.capture_names().flatten().collect();
+            if !names.contains(&"date") && !["year", "month", "day"].iter().all(|g| names.contains(g)) {
+                bail!("Filename pattern needs a `date` group or `year`, `month` and `day` groups: {pattern}");
+            }
+            custom.push(re);
+        }
+        Ok(FilenamePatterns { custom })
+    }
+
+    /// Find a meeting date in `filename`, returning it together with the title
+    /// left once the date is stripped. `None` if no pattern matches.
+    pub fn infer(&self, filename: &str) -> Option<FilenameInfo> {
+        let stem = Path::new(filename)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(filename);
+
+        self.custom
+            .iter()
+            .chain(BUILTIN.iter())
+            .find_map(|re| re.captures_iter(stem).find_map(|caps| info_from(stem, &caps)))
+    }
+}
+
+/// Date and title recovered from a file name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameInfo {
+    /// Canonical UTC RFC 3339; times without a zone are taken as UTC
+    pub date: String,
+    /// Empty if the name was nothing but the date
+    pub title: String,
+}
+
+fn info_from(stem: &str, caps: &Captures) -> Option<FilenameInfo> {
+    let date = match caps.name("date") {
+        Some(m) => dates::parse(m.as_str())?,
+        None => {
+            let num = |name: &str| caps.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+            let day = NaiveDate::from_ymd_opt(num("year")? as i32, num("month")?, num("day")?)?;
+            day.and_hms_opt(
+                num("hour").unwrap_or(0),
+                num("minute").unwrap_or(0),
+                num("second").unwrap_or(0),
+            )?
+            .and_utc()
+        }
+    };
+
+    let title = match caps.name("title") {
+        Some(m) => clean_title(m.as_str()),
+        None => {
+            let stamp = caps.name("stamp").or_else(|| caps.name("date")).or_else(|| caps.get(0))?;
+            clean_title(&format!("{} {}", &stem[..stamp.start()], &stem[stamp.end()..]))
+        }
+    };
+
+    Some(FilenameInfo {
+        date: date.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        title,
+    })
+}
+
+/// Underscores become spaces, dashes that join words become spaces, and
+/// separators left dangling by the removed date are trimmed:
+/// `" Weekly Sync - Platform"` stays readable, `"zoom_"` becomes `"zoom"`.
+fn clean_title(s: &str) -> String {
+    let words: Vec<String> = s
+        .split([' ', '_'])
+        .filter(|w| !w.is_empty())
+        .map(|w| if w == "-" { w.to_string() } else { w.replace('-', " ") })
+        .collect();
+    words
+        .join(" ")
+        .trim_matches(|c: char| c == '-' || c == '.' || c.is_whitespace())
+        .to_string()
+}
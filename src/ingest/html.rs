@@ -0,0 +1,476 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::db::models::NewTranscript;
+use crate::ingest::markdown::filename_to_title;
+use crate::ingest::text::text_to_transcript;
+use crate::ingest::vtt;
+
+/// Attributes that carry speaker, time or date hints, with either quote style.
+static ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\b(class|itemprop|data-[\w-]+|name|property|content|datetime)\s*=\s*(?:"([^"]*)"|'([^']*)')"#,
+    )
+    .expect("valid attribute regex")
+});
+
+/// Named and numeric character references.
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("valid entity regex")
+});
+
+/// Elements whose content is never visible text.
+const SKIPPED: &[&str] = &["script", "style", "noscript", "template", "svg"];
+
+/// Elements that start a new line of text.
+const BLOCKS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "br", "dd", "details", "div", "dl", "dt",
+    "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr",
+    "li", "main", "nav", "ol", "p", "pre", "section", "summary", "table", "tr", "ul",
+];
+
+/// Elements that never have a close tag, so can't hold captured text.
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Longest speaker or time label kept from a captured element; anything longer
+/// is body text in a wrapper that happens to have a matching class.
+const MAX_LABEL_LEN: usize = 80;
+
+/// Written for `<th>` cells so header rows can be dropped from the text.
+const HEADER_CELL: char = '\u{1}';
+
+/// Parse a saved HTML transcript page (Google Meet, Teams, Otter, Zoom).
+///
+/// Markup is reduced to text lines and fed to the plain-text speaker
+/// detection. Elements whose class names mention a speaker or timestamp are
+/// stitched into `[TS] Name: text` lines, table rows become one line per row,
+/// and speaker/time headings split across lines are rejoined. Pages with no
+/// recognisable structure are stored as readable plain text.
+pub fn parse_html(content: &str, filename: &str, default_source: Option<&str>) -> Result<NewTranscript> {
+    let page = html_to_text(content);
+    let text = join_split_headings(&page.text);
+
+    let title = page
+        .title
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| filename_to_title(filename));
+
+    Ok(text_to_transcript(
+        &text,
+        title,
+        page.date.unwrap_or_default(),
+        default_source.unwrap_or("html"),
+        None,
+    ))
+}
+
+/// True if text starts like an HTML document (`<!DOCTYPE html>` or `<html>`).
+pub fn looks_like_html(content: &str) -> bool {
+    let head: String = content
+        .trim_start()
+        .chars()
+        .take(15)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+struct Page {
+    title: Option<String>,
+    date: Option<String>,
+    text: String,
+}
+
+/// What an element's class names say about its content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Speaker,
+    Time,
+}
+
+/// An open speaker/time element whose text is being captured.
+struct Capture {
+    tag: String,
+    role: Role,
+    depth: usize,
+    text: String,
+}
+
+/// Strip markup into lines of text, capturing `<title>`, a publication date
+/// from `<meta>`/`<time>`, and speaker/time hints from class names.
+fn html_to_text(html: &str) -> Page {
+    let mut page = Page {
+        title: None,
+        date: None,
+        text: String::new(),
+    };
+    let mut capture: Option<Capture> = None;
+    let mut pending_speaker: Option<String> = None;
+    let mut pending_time: Option<String> = None;
+
+    let mut rest = html;
+    loop {
+        let Some(lt) = rest.find('<') else {
+            emit_text(&mut page.text, rest, &mut capture, &mut pending_speaker, &mut pending_time);
+            break;
+        };
+        emit_text(&mut page.text, &rest[..lt], &mut capture, &mut pending_speaker, &mut pending_time);
+        rest = &rest[lt..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        let Some(gt) = rest.find('>') else {
+            emit_text(&mut page.text, rest, &mut capture, &mut pending_speaker, &mut pending_time);
+            break;
+        };
+        let inner = &rest[1..gt];
+        rest = &rest[gt + 1..];
+
+        let closing = inner.starts_with('/');
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_start_matches('/');
+        let name_end = inner
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+        if name.is_empty() || name.starts_with('!') || name.starts_with('?') {
+            continue;
+        }
+        let attrs = &inner[name_end..];
+
+        if !closing && (name == "title" || SKIPPED.contains(&name.as_str())) {
+            // Raw content up to the matching close tag
+            let close = format!("</{name}");
+            let end = find_ignore_case(rest, &close).unwrap_or(rest.len());
+            if name == "title" && page.title.is_none() {
+                page.title = Some(collapse_whitespace(&decode_entities(&rest[..end])).trim().to_string());
+            }
+            rest = &rest[end..];
+            rest = rest.find('>').map_or("", |gt| &rest[gt + 1..]);
+            continue;
+        }
+
+        let attr_values: Vec<(String, String)> = ATTR
+            .captures_iter(attrs)
+            .map(|c| {
+                let value = c.get(2).or(c.get(3)).map_or("", |m| m.as_str());
+                (c[1].to_ascii_lowercase(), decode_entities(value))
+            })
+            .collect();
+
+        if !closing && page.date.is_none() {
+            page.date = page_date(&name, &attr_values);
+        }
+
+        // Speaker/time capture. Captures only hold inline content: a block
+        // child means the element wraps more than a label, so its text so far
+        // goes back into the body and the child is handled normally.
+        if capture.as_ref().is_some_and(|cap| {
+            !closing && cap.tag != name && BLOCKS.contains(&name.as_str())
+        }) {
+            release(&mut page.text, &mut capture, &mut pending_speaker, &mut pending_time);
+        }
+        if let Some(cap) = capture.as_mut() {
+            if cap.tag == name {
+                if closing {
+                    cap.depth -= 1;
+                } else {
+                    cap.depth += 1;
+                }
+            }
+            if cap.depth == 0 {
+                let value = collapse_whitespace(&cap.text).trim().trim_end_matches(':').trim().to_string();
+                if value.len() > MAX_LABEL_LEN {
+                    release(&mut page.text, &mut capture, &mut pending_speaker, &mut pending_time);
+                } else {
+                    match capture.take().expect("capture is open").role {
+                        Role::Speaker if !value.is_empty() => pending_speaker = Some(value),
+                        Role::Time if is_timestamp(&value) => pending_time = Some(value),
+                        _ => {}
+                    }
+                }
+            }
+            continue;
+        } else if !closing && !self_closing && !VOID.contains(&name.as_str()) {
+            if let Some(role) = role_of(&attr_values) {
+                capture = Some(Capture {
+                    tag: name.clone(),
+                    role,
+                    depth: 1,
+                    text: String::new(),
+                });
+                continue;
+            }
+        }
+
+        if BLOCKS.contains(&name.as_str()) {
+            page.text.push('\n');
+        } else if name == "td" {
+            page.text.push('\t');
+        } else if name == "th" {
+            page.text.push('\t');
+            page.text.push(HEADER_CELL);
+        }
+    }
+
+    // A capture never closed (unbalanced markup) is body text, not a label
+    release(&mut page.text, &mut capture, &mut pending_speaker, &mut pending_time);
+    page
+}
+
+/// Close an open capture, writing what it collected as ordinary text.
+fn release(
+    out: &mut String,
+    capture: &mut Option<Capture>,
+    pending_speaker: &mut Option<String>,
+    pending_time: &mut Option<String>,
+) {
+    if let Some(cap) = capture.take() {
+        out.push('\n');
+        push_text(out, &cap.text, pending_speaker, pending_time);
+    }
+}
+
+/// Append visible text, opening a `[TS] Name: ` line first if a speaker or
+/// timestamp element was just seen.
+fn emit_text(
+    out: &mut String,
+    raw: &str,
+    capture: &mut Option<Capture>,
+    pending_speaker: &mut Option<String>,
+    pending_time: &mut Option<String>,
+) {
+    if raw.is_empty() {
+        return;
+    }
+    let text = decode_entities(raw);
+
+    if let Some(cap) = capture.as_mut() {
+        cap.text.push_str(&text);
+        return;
+    }
+    push_text(out, &text, pending_speaker, pending_time);
+}
+
+/// `emit_text` for text that is already decoded and not being captured.
+fn push_text(
+    out: &mut String,
+    text: &str,
+    pending_speaker: &mut Option<String>,
+    pending_time: &mut Option<String>,
+) {
+    let text = collapse_whitespace(text);
+    if text.trim().is_empty() {
+        if !text.is_empty() && !out.ends_with([' ', '\n', '\t']) {
+            out.push(' ');
+        }
+        return;
+    }
+
+    if pending_speaker.is_some() || pending_time.is_some() {
+        out.push('\n');
+        if let Some(ts) = pending_time.take() {
+            out.push_str(&format!("[{ts}] "));
+        }
+        if let Some(name) = pending_speaker.take() {
+            out.push_str(&format!("{name}: "));
+        }
+        out.push_str(text.trim_start());
+    } else {
+        out.push_str(&text);
+    }
+}
+
+/// Classify an element by its class, itemprop and data-* attribute values.
+fn role_of(attrs: &[(String, String)]) -> Option<Role> {
+    let mut role = None;
+    for (name, value) in attrs {
+        if name != "class" && name != "itemprop" && !name.starts_with("data-") {
+            continue;
+        }
+        let value = value.to_ascii_lowercase();
+        for token in value.split(|c: char| !c.is_ascii_alphanumeric()) {
+            if token.contains("speaker") {
+                return Some(Role::Speaker);
+            }
+            if matches!(token, "time" | "timestamp" | "ts" | "starttime") {
+                role = Some(Role::Time);
+            }
+        }
+    }
+    role
+}
+
+/// A publication date from `<meta name="date">`-style tags or `<time datetime>`.
+fn page_date(tag: &str, attrs: &[(String, String)]) -> Option<String> {
+    let get = |key: &str| attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    let value = match tag {
+        "meta" => {
+            let key = get("name").or(get("property"))?.to_ascii_lowercase();
+            if !matches!(
+                key.as_str(),
+                "date" | "dcterms.created" | "article:published_time" | "og:published_time"
+            ) {
+                return None;
+            }
+            get("content")?
+        }
+        "time" => get("datetime")?,
+        _ => return None,
+    };
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Clean up the extracted lines: drop table header rows, turn table rows into `[TS] Name: text` or
+/// `Name: text`, and rejoin speaker and timestamp headings that markup put on
+/// separate lines (`Alice` / `0:03` becomes `Alice  0:03`).
+fn join_split_headings(text: &str) -> String {
+    let lines: Vec<String> = text
+        .lines()
+        .filter(|l| !l.contains(HEADER_CELL))
+        .map(table_row)
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        if let Some(next) = lines.get(i + 1) {
+            if looks_like_name(line) && is_timestamp(next) {
+                out.push(format!("{line}  {next}"));
+                i += 2;
+                continue;
+            }
+            if is_timestamp(line) && looks_like_name(next) {
+                out.push(format!("[{line}] {next}"));
+                i += 2;
+                continue;
+            }
+        }
+        out.push(line.clone());
+        i += 1;
+    }
+
+    out.join("\n")
+}
+
+/// Flatten a tab-separated table row into a speaker line when its cells look
+/// like name / time / text columns.
+fn table_row(line: &str) -> String {
+    if !line.contains('\t') {
+        return line.to_string();
+    }
+    let cells: Vec<&str> = line
+        .split('\t')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    let ts = cells.iter().position(|c| is_timestamp(c));
+    let others: Vec<&str> = cells
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != ts)
+        .map(|(_, c)| *c)
+        .collect();
+
+    match (ts, others.as_slice()) {
+        (Some(t), [name, text @ ..]) if looks_like_name(name) && !text.is_empty() => {
+            format!("[{}] {name}: {}", cells[t], text.join(" "))
+        }
+        (None, [name, text @ ..]) if looks_like_name(name) && !text.is_empty() => {
+            format!("{name}: {}", text.join(" "))
+        }
+        _ => cells.join(" "),
+    }
+}
+
+/// A short label that could be a speaker name: one to four words, starting
+/// with a letter, without sentence punctuation.
+fn looks_like_name(s: &str) -> bool {
+    let s = s.trim_end_matches(':');
+    !s.is_empty()
+        && s.len() <= 40
+        && s.split_whitespace().count() <= 4
+        && s.chars().next().is_some_and(|c| c.is_alphabetic())
+        && s
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '.' | '\'' | '-' | '_'))
+        && !s.ends_with('.')
+}
+
+/// A cue-style timestamp, optionally with a 12-hour clock suffix (`10:02 AM`).
+fn is_timestamp(s: &str) -> bool {
+    let lower = s.trim().to_ascii_lowercase();
+    let clock = lower
+        .strip_suffix("am")
+        .or_else(|| lower.strip_suffix("pm"))
+        .unwrap_or(&lower);
+    vtt::is_timestamp(clock.trim_end())
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+/// Decode named entities common in transcript pages plus numeric references.
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    ENTITY
+        .replace_all(s, |c: &regex::Captures| {
+            let entity = &c[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "ndash" => Some('–'),
+                "mdash" => Some('—'),
+                "hellip" => Some('…'),
+                "rsquo" => Some('’'),
+                "lsquo" => Some('‘'),
+                "rdquo" => Some('”'),
+                "ldquo" => Some('“'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| c[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use std::path::{Path, PathBuf};
+
+/// Name of the per-directory ignore file.
+pub const IGNORE_FILE: &str = ".tssignore";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// What directory ingest leaves out: `--exclude` globs, `.tssignore` files
+/// and (unless asked for) hidden files and directories.
+#[derive(Debug, Clone, Default)]
+pub struct Ignore {
+    excludes: Vec<IgnorePattern>,
+    include_hidden: bool,
+}
+
+/// One gitignore-style line.
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    glob: Pattern,
+    /// `!pattern` re-includes what an earlier pattern excluded
+    negated: bool,
+    /// `pattern/` only matches directories
+    dir_only: bool,
+    /// Patterns with a `/` match the path relative to their base directory;
+    /// others match the file or directory name at any depth
+    anchored: bool,
+}
+
+/// Patterns from one source, relative to the directory they apply to.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    base: PathBuf,
+    patterns: Vec<IgnorePattern>,
+}
+
+impl Ignore {
+    pub fn new(excludes: &[String], include_hidden: bool) -> Result<Self> {
+        let excludes = excludes
+            .iter()
+            .filter_map(|line| parse_line(line).transpose())
+            .collect::<Result<_>>()?;
+        Ok(Ignore {
+            excludes,
+            include_hidden,
+        })
+    }
+
+    /// The same filtering, but with hidden files and directories let through.
+    pub fn with_hidden(&self) -> Ignore {
+        Ignore {
+            include_hidden: true,
+            ..self.clone()
+        }
+    }
+
+    /// Layers in effect at the top of a directory walk: `--exclude`, then the
+    /// root's own `.tssignore`.
+    pub fn root_layers(&self, root: &Path) -> Vec<Layer> {
+        let mut layers = vec![Layer {
+            base: root.to_path_buf(),
+            patterns: self.excludes.clone(),
+        }];
+        layers.extend(read_ignore_file(root));
+        layers
+    }
+
+    /// Layers for the children of `dir`: the parent's plus `dir/.tssignore`.
+    pub fn enter(&self, layers: &[Layer], dir: &Path) -> Vec<Layer> {
+        let mut layers = layers.to_vec();
+        layers.extend(read_ignore_file(dir));
+        layers
+    }
+
+    /// Whether `path`, found while walking under `layers`, should be skipped.
+    pub fn is_ignored(&self, layers: &[Layer], path: &Path, is_dir: bool) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if !self.include_hidden && is_hidden(name) {
+            return true;
+        }
+
+        let mut ignored = false;
+        for layer in layers {
+            let Ok(relative) = path.strip_prefix(&layer.base) else {
+                continue;
+            };
+            for pattern in &layer.patterns {
+                if pattern.dir_only && !is_dir {
+                    continue;
+                }
+                let matched = if pattern.anchored {
+                    pattern.glob.matches_path_with(relative, MATCH_OPTIONS)
+                } else {
+                    pattern.glob.matches_with(name, MATCH_OPTIONS)
+                };
+                if matched {
+                    ignored = !pattern.negated;
+                }
+            }
+        }
+        ignored
+    }
+
+    /// Whether a file below `root` (e.g. reported by the watcher) would have
+    /// been skipped by a directory walk from `root`.
+    pub fn is_ignored_below(&self, root: &Path, path: &Path) -> bool {
+        self.check_components(root, path, true)
+    }
+
+    /// `--exclude` and hidden-file filtering for a path matched by a glob
+    /// argument, applied to the components below `base` (the pattern's
+    /// literal prefix). `.tssignore` files only apply to directory walks.
+    pub fn excludes_match(&self, base: &Path, path: &Path) -> bool {
+        self.check_components(base, path, false)
+    }
+
+    /// Check each directory on the way from `root` down to `path`, then the
+    /// path itself, as a walk would.
+    fn check_components(&self, root: &Path, path: &Path, read_files: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        let mut layers = if read_files {
+            self.root_layers(root)
+        } else {
+            vec![Layer {
+                base: root.to_path_buf(),
+                patterns: self.excludes.clone(),
+            }]
+        };
+
+        let mut current = root.to_path_buf();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            current.push(component);
+            let is_dir = components.peek().is_some();
+            if self.is_ignored(&layers, &current, is_dir) {
+                return true;
+            }
+            if is_dir && read_files {
+                layers = self.enter(&layers, &current);
+            }
+        }
+        false
+    }
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('.') && name != "." && name != ".."
+}
+
+/// Parse `dir/.tssignore`, if there is one. Unreadable files are skipped,
+/// and so are bad patterns, with a warning.
+fn read_ignore_file(dir: &Path) -> Option<Layer> {
+    let path = dir.join(IGNORE_FILE);
+    let content = std::fs::read_to_string(&path).ok()?;
+    let patterns = content
+        .lines()
+        .filter_map(|line| match parse_line(line) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                tracing::warn!("{}: {e:#}", path.display());
+                None
+            }
+        })
+        .collect();
+    Some(Layer {
+        base: dir.to_path_buf(),
+        patterns,
+    })
+}
+
+/// Parse one gitignore-style line; blank lines and `#` comments give `None`.
+fn parse_line(line: &str) -> Result<Option<IgnorePattern>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let anchored = line.contains('/');
+    let line = line.trim_start_matches('/');
+
+    let glob = Pattern::new(line).with_context(|| format!("Invalid ignore pattern: {line}"))?;
+    Ok(Some(IgnorePattern {
+        glob,
+        negated,
+        dir_only,
+        anchored,
+    }))
+}
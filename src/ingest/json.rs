@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use serde::Deserialize;
 
 use crate::db::models::{NewActionItem, NewSegment, NewTranscript};
+use crate::ingest::{assemblyai, content_hash, deepgram, rev, whisper, ParsedRecord};
 
 /// Native TSS JSON format.
 #[derive(Debug, Deserialize)]
@@ -42,6 +43,7 @@ pub struct JsonSegment {
     // Also accept start_time/end_time
     pub start_time: Option<f64>,
     pub end_time: Option<f64>,
+    pub metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,14 +61,104 @@ pub struct JsonActionItem {
     pub priority: Option<String>,
 }
 
-/// Parse a JSON string into a NewTranscript.
-pub fn parse_json(content: &str, default_source: Option<&str>) -> Result<NewTranscript> {
-    let jt: JsonTranscript =
+/// Parse a JSON string into a NewTranscript. Tool-specific shapes (Whisper,
+/// AssemblyAI, Deepgram, Rev) are detected from their fields; everything else
+/// is read as native TSS JSON.
+pub fn parse_json(content: &str, filename: &str, default_source: Option<&str>) -> Result<NewTranscript> {
+    let value: serde_json::Value =
         serde_json::from_str(content).context("Failed to parse JSON transcript")?;
+    parse_json_value(value, filename, default_source)
+}
+
+/// True if the document is a top-level JSON array rather than a single object.
+pub fn is_json_array(content: &str) -> bool {
+    content.trim_start_matches('\u{feff}').trim_start().starts_with('[')
+}
+
+/// True if the first non-empty line is a complete JSON object and more lines
+/// follow, i.e. newline-delimited JSON rather than one pretty-printed object.
+pub fn looks_like_ndjson(content: &str) -> bool {
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty());
+    let first_is_object = lines.next().is_some_and(|l| {
+        l.starts_with('{') && serde_json::from_str::<serde_json::Value>(l).is_ok()
+    });
+    first_is_object && lines.next().is_some()
+}
+
+/// Parse a top-level JSON array; each element is parsed like a single JSON
+/// transcript. A malformed element fails only its own record. Elements are
+/// keyed by content, so an element without an `id` keeps its ID when others
+/// are added, removed or reordered.
+pub fn parse_json_array(
+    content: &str,
+    filename: &str,
+    default_source: Option<&str>,
+) -> Result<Vec<ParsedRecord>> {
+    let items: Vec<serde_json::Value> = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .context("Failed to parse JSON array")?;
+
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let raw = value.to_string();
+            ParsedRecord {
+                number: i + 1,
+                key: Some(content_hash(&raw)),
+                raw,
+                transcript: parse_json_value(value, filename, default_source),
+            }
+        })
+        .collect())
+}
+
+/// Parse newline-delimited JSON (`.jsonl` / `.ndjson`), one transcript per
+/// line. Blank lines are skipped; a malformed line fails only its own record.
+/// Lines are keyed by content, like array elements.
+pub fn parse_ndjson(content: &str, filename: &str, default_source: Option<&str>) -> Vec<ParsedRecord> {
+    content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let raw = line.trim().to_string();
+            ParsedRecord {
+                number: i + 1,
+                key: Some(content_hash(&raw)),
+                raw,
+                transcript: parse_json(line, filename, default_source),
+            }
+        })
+        .collect()
+}
+
+fn parse_json_value(
+    value: serde_json::Value,
+    filename: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    if whisper::is_whisper(&value) {
+        return whisper::parse_whisper(value, filename, default_source);
+    }
+    if assemblyai::is_assemblyai(&value) {
+        return assemblyai::parse_assemblyai(value, filename, default_source);
+    }
+    if deepgram::is_deepgram(&value) {
+        return deepgram::parse_deepgram(value, filename, default_source);
+    }
+    if rev::is_rev(&value) {
+        return rev::parse_rev(value, filename, default_source);
+    }
+
+    let jt: JsonTranscript =
+        serde_json::from_value(value).context("Failed to parse JSON transcript")?;
 
-    let id = jt
-        .id
-        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let id = jt.id.unwrap_or_default();
 
     let title = jt.title.unwrap_or_else(|| "Untitled".to_string());
     let date = jt.date.unwrap_or_default();
@@ -136,6 +228,7 @@ pub fn parse_json(content: &str, default_source: Option<&str>) -> Result<NewTran
             start_time: s.start.or(s.start_time).unwrap_or(0.0),
             end_time: s.end.or(s.end_time).unwrap_or(0.0),
             segment_index: i as i64,
+            metadata: s.metadata,
         })
         .collect();
 
@@ -181,6 +274,7 @@ pub fn parse_json(content: &str, default_source: Option<&str>) -> Result<NewTran
         summary,
         raw_text,
         metadata,
+        content_hash: None,
         speakers,
         segments,
         tags,
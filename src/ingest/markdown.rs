@@ -5,7 +5,7 @@ use crate::db::models::{NewSegment, NewTranscript};
 /// Parse a markdown file with optional YAML frontmatter into a NewTranscript.
 ///
 /// Expected format:
-/// ```
+/// ```text
 /// ---
 /// title: Meeting Title
 /// date: 2026-01-15
@@ -81,6 +81,7 @@ pub fn parse_markdown(content: &str, filename: &str, default_source: Option<&str
             start_time: 0.0,
             end_time: 0.0,
             segment_index: 0,
+            metadata: None,
         }]
     } else {
         segments
@@ -96,7 +97,7 @@ pub fn parse_markdown(content: &str, filename: &str, default_source: Option<&str
         }
     }
 
-    let id = uuid::Uuid::new_v4().to_string();
+    let id = String::new();
     let raw_text = body.trim().to_string();
 
     Ok(NewTranscript {
@@ -108,6 +109,7 @@ pub fn parse_markdown(content: &str, filename: &str, default_source: Option<&str
         summary: String::new(),
         raw_text,
         metadata,
+        content_hash: None,
         speakers,
         segments,
         tags,
@@ -138,7 +140,7 @@ fn split_frontmatter(content: &str) -> (Option<String>, &str) {
     }
 }
 
-fn filename_to_title(filename: &str) -> String {
+pub(crate) fn filename_to_title(filename: &str) -> String {
     let stem = std::path::Path::new(filename)
         .file_stem()
         .and_then(|s| s.to_str())
@@ -182,6 +184,7 @@ fn parse_speaker_segments(body: &str) -> Vec<NewSegment> {
                 start_time: *timestamp,
                 end_time: 0.0,
                 segment_index: i as i64,
+                metadata: None,
             });
         }
     }
@@ -194,7 +197,7 @@ fn parse_speaker_segments(body: &str) -> Vec<NewSegment> {
     segments
 }
 
-fn parse_timestamp(ts: &str) -> f64 {
+pub(crate) fn parse_timestamp(ts: &str) -> f64 {
     let parts: Vec<&str> = ts.split(':').collect();
     match parts.len() {
         2 => {
@@ -43,6 +43,7 @@ pub fn migrate_from_python_db(db: &Database, source_path: &Path, dry_run: bool)
 
     info!("Found {} transcripts to migrate", transcript_rows.len());
 
+    let mut pending = Vec::new();
     for trow in &transcript_rows {
         if !dry_run && db.transcript_exists(&trow.id)? {
             stats.skipped += 1;
@@ -100,6 +101,7 @@ pub fn migrate_from_python_db(db: &Database, source_path: &Path, dry_run: bool)
             summary: trow.summary.clone(),
             raw_text: trow.raw_text.clone(),
             metadata,
+            content_hash: None,
             speakers,
             segments,
             tags,
@@ -115,14 +117,30 @@ pub fn migrate_from_python_db(db: &Database, source_path: &Path, dry_run: bool)
                 new_t.segments.len()
             );
         } else {
-            db.insert_transcript(&new_t)?;
+            pending.push(new_t);
+            if pending.len() >= INSERT_BATCH {
+                insert_pending(db, &mut pending)?;
+            }
         }
         stats.imported += 1;
     }
+    insert_pending(db, &mut pending)?;
 
     Ok(stats)
 }
 
+/// Transcripts written per transaction.
+const INSERT_BATCH: usize = 500;
+
+/// Write a batch of migrated transcripts, then fail with the first error.
+fn insert_pending(db: &Database, pending: &mut Vec<NewTranscript>) -> Result<()> {
+    for result in db.insert_many(pending)? {
+        result?;
+    }
+    pending.clear();
+    Ok(())
+}
+
 #[derive(Debug, Default)]
 pub struct MigrateStats {
     pub imported: usize,
@@ -159,6 +177,7 @@ fn read_segments(src: &Connection, transcript_id: &str) -> Result<Vec<NewSegment
             start_time: row.get(2)?,
             end_time: row.get(3)?,
             segment_index: row.get(4)?,
+            metadata: None,
         })
     })?;
     rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
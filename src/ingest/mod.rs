@@ -1,29 +1,72 @@
+pub mod archive;
+pub mod assemblyai;
+pub mod csv;
+pub mod deepgram;
+pub mod docx;
+pub mod filename;
+pub mod html;
+pub mod ignore;
 pub mod json;
 pub mod markdown;
 pub mod migrate;
+pub mod pdf;
+pub mod rev;
+pub mod rules;
+pub mod srt;
 pub mod text;
+pub mod vtt;
+pub mod watch;
+pub mod whisper;
 
 use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
 use tracing::info;
 
-use crate::db::models::NewTranscript;
+use crate::db::models::{NewTranscript, UpsertOutcome};
 use crate::db::Database;
+use crate::ingest::archive::ArchiveKind;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Format {
     Json,
+    /// Newline-delimited JSON, one transcript per line
+    Ndjson,
     Markdown,
     Text,
+    Vtt,
+    Srt,
+    Html,
+    /// Segment table, one row per segment
+    Csv,
+    Tsv,
+    Docx,
+    Pdf,
 }
 
+/// Records classified per flush, and so at most transcripts per transaction.
+const BATCH_SIZE: usize = 500;
+
 impl Format {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "json" => Some(Format::Json),
+            "ndjson" | "jsonl" => Some(Format::Ndjson),
             "markdown" | "md" => Some(Format::Markdown),
             "text" | "txt" => Some(Format::Text),
+            "vtt" | "webvtt" => Some(Format::Vtt),
+            "srt" | "subrip" => Some(Format::Srt),
+            "html" | "htm" => Some(Format::Html),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            "docx" | "word" => Some(Format::Docx),
+            "pdf" => Some(Format::Pdf),
             _ => None,
         }
     }
@@ -31,29 +74,176 @@ impl Format {
     pub fn detect_from_extension(path: &Path) -> Option<Self> {
         match path.extension().and_then(|e| e.to_str()) {
             Some("json") => Some(Format::Json),
+            Some("jsonl" | "ndjson") => Some(Format::Ndjson),
             Some("md" | "markdown") => Some(Format::Markdown),
             Some("txt" | "text") => Some(Format::Text),
+            Some("vtt") => Some(Format::Vtt),
+            Some("srt") => Some(Format::Srt),
+            Some("html" | "htm") => Some(Format::Html),
+            Some("csv") => Some(Format::Csv),
+            Some("tsv") => Some(Format::Tsv),
+            Some("docx") => Some(Format::Docx),
+            Some("pdf") => Some(Format::Pdf),
             _ => None,
         }
     }
+
+    /// Binary document formats, read as bytes rather than UTF-8 text.
+    pub fn is_binary(self) -> bool {
+        matches!(self, Format::Docx | Format::Pdf)
+    }
 }
 
-/// Ingest one or more paths (files or directories). Returns count of ingested transcripts.
-pub fn ingest_paths(
-    db: &Database,
-    paths: &[String],
-    default_source: Option<&str>,
-    format_override: Option<Format>,
-    dry_run: bool,
-) -> Result<usize> {
-    let mut count = 0;
+/// Options shared by all ingest entry points.
+#[derive(Debug, Clone, Default)]
+pub struct IngestOptions<'a> {
+    /// Source label for formats that don't carry one
+    pub default_source: Option<&'a str>,
+    /// Force a format instead of detecting it from the extension
+    pub format_override: Option<Format>,
+    /// Preview without writing to the database
+    pub dry_run: bool,
+    /// Replace transcripts whose ID already exists instead of skipping them
+    pub update: bool,
+    /// Record per-file failures and continue instead of aborting the run
+    pub keep_going: bool,
+    /// Column mapping for CSV/TSV segment tables
+    pub columns: Option<csv::ColumnMap>,
+    /// Configured patterns for dates and titles embedded in file names
+    pub filename_patterns: filename::FilenamePatterns,
+    /// `[[ingest.rules]]` routing files to a source, tags and format
+    pub rules: rules::Rules,
+    /// Tags added to every transcript (filled per file from matching rules)
+    pub tags: Vec<String>,
+    /// `--exclude`, `.tssignore` and hidden-file filtering for directory walks
+    pub ignore: ignore::Ignore,
+}
+
+impl IngestOptions<'_> {
+    /// Options for one file with its matching rules applied. An explicit
+    /// `--source` or `--format` still wins over a rule.
+    fn for_path(&self, path: &Path) -> IngestOptions<'_> {
+        let route = self.rules.route(path);
+        let mut opts = self.clone();
+        opts.default_source = self.default_source.or(route.source);
+        opts.format_override = self.format_override.or(route.format);
+        for tag in route.tags {
+            if !opts.tags.contains(&tag) {
+                opts.tags.push(tag);
+            }
+        }
+        opts
+    }
+}
+
+/// What happened to a single input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Ingested,
+    Updated,
+    Unchanged,
+    Duplicate,
+    Failed,
+}
+
+impl FileStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileStatus::Ingested => "ingested",
+            FileStatus::Updated => "updated",
+            FileStatus::Unchanged => "unchanged",
+            FileStatus::Duplicate => "duplicate",
+            FileStatus::Failed => "failed",
+        }
+    }
+}
+
+impl From<UpsertOutcome> for FileStatus {
+    fn from(outcome: UpsertOutcome) -> Self {
+        match outcome {
+            UpsertOutcome::Inserted => FileStatus::Ingested,
+            UpsertOutcome::Updated => FileStatus::Updated,
+            UpsertOutcome::Unchanged => FileStatus::Unchanged,
+        }
+    }
+}
+
+/// Per-file outcome, collected for the ingest report.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: String,
+    pub status: FileStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome counts for an ingest run.
+#[derive(Debug, Default, Serialize)]
+pub struct IngestStats {
+    /// New transcripts added (or that would be added, in dry-run mode)
+    pub ingested: usize,
+    /// Existing transcripts replaced with changed content
+    pub updated: usize,
+    /// Already ingested with identical content
+    pub unchanged: usize,
+    /// ID already present with different content, skipped because `update` is off
+    pub duplicates: usize,
+    /// Inputs that could not be read, parsed or stored. A file that fails to
+    /// parse only gets here with `keep_going`; otherwise it ends the run.
+    pub failed: usize,
+    /// One entry per input, in processing order
+    pub files: Vec<FileReport>,
+}
+
+impl IngestStats {
+    fn record(&mut self, origin: &str, transcript: &NewTranscript, status: FileStatus) {
+        match status {
+            FileStatus::Ingested => self.ingested += 1,
+            FileStatus::Updated => self.updated += 1,
+            FileStatus::Unchanged => self.unchanged += 1,
+            FileStatus::Duplicate => self.duplicates += 1,
+            FileStatus::Failed => self.failed += 1,
+        }
+        self.files.push(FileReport {
+            path: origin.to_string(),
+            status,
+            id: Some(transcript.id.clone()),
+            title: Some(transcript.title.clone()),
+            error: None,
+        });
+    }
+
+    fn record_failure(&mut self, origin: &str, error: &anyhow::Error) {
+        self.failed += 1;
+        self.files.push(FileReport {
+            path: origin.to_string(),
+            status: FileStatus::Failed,
+            id: None,
+            title: None,
+            error: Some(format!("{error:#}")),
+        });
+    }
+}
+
+/// Ingest one or more paths (files, directories or glob patterns). Files are
+/// parsed on a pool of worker threads and stored by a single writer, in the
+/// order they were listed.
+pub fn ingest_paths(db: &Database, paths: &[String], opts: &IngestOptions) -> Result<IngestStats> {
+    let mut stats = IngestStats::default();
+    let mut files = Vec::new();
 
     for path_str in paths {
         let path = Path::new(path_str);
         if path.is_dir() {
-            count += ingest_directory(db, path, default_source, format_override, dry_run)?;
+            let layers = opts.ignore.root_layers(path);
+            collect_directory(path, &layers, opts, &mut files, &mut stats)?;
         } else if path.is_file() {
-            count += ingest_file(db, path, default_source, format_override, dry_run)?;
+            files.push(path.to_path_buf());
         } else {
             // Try glob pattern
             let matches: Vec<_> = glob::glob(path_str)
@@ -65,143 +255,714 @@ pub fn ingest_paths(
                 bail!("No files found matching: {path_str}");
             }
 
-            for entry in matches {
-                if entry.is_file() {
-                    count += ingest_file(db, &entry, default_source, format_override, dry_run)?;
-                }
+            // Only the part of each match the pattern expanded is checked
+            // for hidden components and --exclude; the literal prefix was
+            // named explicitly, and so were hidden files if the pattern has
+            // a component starting with `.`
+            let base = glob_base(path_str);
+            let wants_hidden = Path::new(path_str)
+                .components()
+                .skip(base.components().count())
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+            let ignore = if wants_hidden {
+                opts.ignore.with_hidden()
+            } else {
+                opts.ignore.clone()
+            };
+            let before = files.len();
+            files.extend(
+                matches
+                    .into_iter()
+                    .filter(|entry| entry.is_file() && !ignore.excludes_match(&base, entry)),
+            );
+            if files.len() == before {
+                bail!("All files matching {path_str} are hidden or excluded (use --hidden, or check --exclude)");
             }
         }
     }
 
-    Ok(count)
+    ingest_files(db, &files, opts, &mut stats)?;
+    Ok(stats)
+}
+
+/// The leading components of a glob pattern that contain no wildcards.
+fn glob_base(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect()
 }
 
 /// Ingest from stdin.
-pub fn ingest_stdin(
-    db: &Database,
-    default_source: Option<&str>,
-    format_override: Option<Format>,
-    dry_run: bool,
-) -> Result<usize> {
-    let mut content = String::new();
+pub fn ingest_stdin(db: &Database, opts: &IngestOptions) -> Result<IngestStats> {
+    let mut bytes = Vec::new();
     std::io::stdin()
-        .read_to_string(&mut content)
+        .read_to_end(&mut bytes)
         .context("Failed to read from stdin")?;
 
-    if content.trim().is_empty() {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
         bail!("Empty input from stdin");
     }
 
-    let format = format_override.unwrap_or_else(|| {
-        // Try to detect: if it starts with { it's JSON, if it starts with --- it's markdown
-        let trimmed = content.trim();
-        if trimmed.starts_with('{') {
-            Format::Json
-        } else if trimmed.starts_with("---") {
-            Format::Markdown
-        } else {
-            Format::Text
+    let format = match opts.format_override {
+        Some(format) => format,
+        None if bytes.starts_with(b"%PDF-") => Format::Pdf,
+        None if bytes.starts_with(b"PK\x03\x04") => Format::Docx,
+        None => {
+            // Try to detect: if it starts with { or [ it's JSON, if it starts with --- it's markdown
+            let content = std::str::from_utf8(&bytes).context("stdin is not valid UTF-8 text")?;
+            let trimmed = content.trim_start_matches('\u{feff}').trim();
+            if trimmed.starts_with('{') && json::looks_like_ndjson(trimmed) {
+                Format::Ndjson
+            } else if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                Format::Json
+            } else if trimmed.starts_with("WEBVTT") {
+                Format::Vtt
+            } else if html::looks_like_html(trimmed) {
+                Format::Html
+            } else if trimmed.starts_with("---") {
+                Format::Markdown
+            } else if srt::looks_like_srt(trimmed) {
+                Format::Srt
+            } else {
+                Format::Text
+            }
         }
-    });
+    };
 
-    let transcript = parse_content(&content, "stdin", format, default_source)?;
+    let mut stats = IngestStats::default();
+    let mut writer = Writer::new(db, opts);
 
-    if dry_run {
-        println!(
-            "  [dry-run] Would ingest: {} ({}, {} segments)",
-            transcript.title,
-            transcript.source,
-            transcript.segments.len()
-        );
-        return Ok(1);
+    // Without a path, the content itself is the only stable identity
+    if !format.is_binary() {
+        let content = std::str::from_utf8(&bytes).context("stdin is not valid UTF-8 text")?;
+        if let Some(records) = parse_records(content, "stdin", "", format, opts)? {
+            for r in records {
+                let hash = content_hash(&r.raw);
+                let record = Record {
+                    origin: format!("stdin#{}", r.number),
+                    path_keyed: false,
+                    transcript: r.transcript.map(|t| with_identity(t, stable_id(&hash), hash)),
+                };
+                writer.add(record, &mut stats)?;
+            }
+            writer.flush(&mut stats)?;
+            return Ok(stats);
+        }
     }
 
-    db.insert_transcript(&transcript)?;
-    info!("Ingested from stdin: {}", transcript.title);
-    Ok(1)
+    let hash = content_hash(&bytes);
+    let transcript = parse_bytes(&bytes, "stdin", "", format, opts.default_source)?;
+    let record = Record {
+        origin: "stdin".to_string(),
+        path_keyed: false,
+        transcript: Ok(with_identity(transcript, stable_id(&hash), hash)),
+    };
+    writer.add(record, &mut stats)?;
+    writer.flush(&mut stats)?;
+    Ok(stats)
 }
 
-fn ingest_directory(
-    db: &Database,
+/// Walk `dir` and collect ingestible files in sorted order, skipping whatever
+/// `layers` (from `--exclude` and the `.tssignore` files seen so far) or the
+/// hidden-file rule leave out.
+fn collect_directory(
     dir: &Path,
-    default_source: Option<&str>,
-    format_override: Option<Format>,
-    dry_run: bool,
-) -> Result<usize> {
-    let mut count = 0;
-
-    let mut entries: Vec<_> = std::fs::read_dir(dir)?
-        .filter_map(|e| e.ok())
-        .collect();
+    layers: &[ignore::Layer],
+    opts: &IngestOptions,
+    files: &mut Vec<PathBuf>,
+    stats: &mut IngestStats,
+) -> Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()));
+    let mut entries: Vec<_> = match entries {
+        Ok(entries) => entries.filter_map(|e| e.ok()).collect(),
+        Err(e) if opts.keep_going => {
+            stats.record_failure(&dir.display().to_string(), &e);
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
     entries.sort_by_key(|e| e.path());
 
     for entry in entries {
         let path = entry.path();
-        if path.is_dir() {
-            count += ingest_directory(db, &path, default_source, format_override, dry_run)?;
-        } else if path.is_file() {
-            // Only process known extensions unless format is overridden
-            if format_override.is_some() || Format::detect_from_extension(&path).is_some() {
-                count += ingest_file(db, &path, default_source, format_override, dry_run)?;
-            }
+        let is_dir = path.is_dir();
+        if opts.ignore.is_ignored(layers, &path, is_dir) {
+            continue;
+        }
+        if is_dir {
+            let layers = opts.ignore.enter(layers, &path);
+            collect_directory(&path, &layers, opts, files, stats)?;
+        } else if path.is_file() && is_ingestible(&path, opts) {
+            files.push(path);
         }
     }
 
-    Ok(count)
+    Ok(())
+}
+
+/// Known transcript extensions and archives; anything at all when the format
+/// is overridden, on the command line or by a rule.
+fn is_ingestible(path: &Path, opts: &IngestOptions) -> bool {
+    opts.format_override.is_some()
+        || Format::detect_from_extension(path).is_some()
+        || ArchiveKind::detect(path).is_some()
+        || opts.rules.route(path).format.is_some()
+}
+
+/// Parse `files` on worker threads and store them from this thread. Results
+/// are written in input order however the workers finish, so reports, IDs
+/// and duplicate handling match a sequential run.
+fn ingest_files(
+    db: &Database,
+    files: &[PathBuf],
+    opts: &IngestOptions,
+    stats: &mut IngestStats,
+) -> Result<()> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(files.len());
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::sync_channel(workers * 4);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = files.get(i) else {
+                    break;
+                };
+                // The writer hung up after an error
+                if tx.send((i, parse_file(path, opts))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut writer = Writer::new(db, opts);
+        let mut ready = BTreeMap::new();
+        let mut next_out = 0;
+        for (i, parsed) in rx {
+            ready.insert(i, parsed);
+            while let Some(parsed) = ready.remove(&next_out) {
+                writer.add_file(&files[next_out], parsed, stats)?;
+                next_out += 1;
+            }
+        }
+        writer.flush(stats)
+    })
 }
 
-fn ingest_file(
+/// Parse and store a single file. Failures are returned rather than recorded,
+/// whatever `keep_going` says.
+pub(crate) fn ingest_file(
     db: &Database,
     path: &Path,
-    default_source: Option<&str>,
-    format_override: Option<Format>,
-    dry_run: bool,
-) -> Result<usize> {
-    let format = format_override
+    opts: &IngestOptions,
+    stats: &mut IngestStats,
+) -> Result<()> {
+    let mut writer = Writer::new(db, opts);
+    for record in parse_file(path, opts)? {
+        writer.add(record, stats)?;
+    }
+    writer.flush(stats)
+}
+
+/// Read and parse one file (or every entry of an archive) into records ready
+/// to store. Runs on worker threads, so it must not touch the database.
+fn parse_file(path: &Path, opts: &IngestOptions) -> Result<Vec<Record>> {
+    let opts = &opts.for_path(path);
+    if let Some(kind) = ArchiveKind::detect(path) {
+        return parse_archive(path, kind, opts);
+    }
+
+    let format = opts
+        .format_override
         .or_else(|| Format::detect_from_extension(path))
         .with_context(|| format!("Cannot determine format for: {}", path.display()))?;
 
-    let content = std::fs::read_to_string(path)
-        .with_context(|| format!("Failed to read: {}", path.display()))?;
+    let content =
+        std::fs::read(path).with_context(|| format!("Failed to read: {}", path.display()))?;
 
     let filename = path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown");
 
-    let transcript = parse_content(&content, filename, format, default_source)?;
+    // Formats without an intrinsic ID are keyed by file path, so an edited
+    // file maps back to the transcript it produced last time.
+    let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let origin = Origin {
+        display: path.display().to_string(),
+        key: format!("path:{}", canonical.display()),
+        provenance: None,
+        date: modified_date(path),
+    };
+
+    content_records(&content, filename, format, &origin, opts)
+}
+
+/// Parse every recognised file inside a zip or tar(.gz) archive. Entries are
+/// keyed by archive path plus inner path, which are also recorded in each
+/// transcript's metadata under `provenance`. With `keep_going`, a bad entry
+/// becomes a failed record instead of failing the whole archive.
+fn parse_archive(path: &Path, kind: ArchiveKind, opts: &IngestOptions) -> Result<Vec<Record>> {
+    let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let date = modified_date(path);
+    let wanted = |name: &str| {
+        opts.format_override.is_some() || Format::detect_from_extension(Path::new(name)).is_some()
+    };
+    let mut entries: Vec<(String, Vec<Record>)> = Vec::new();
+
+    archive::read_entries(path, kind, wanted, |entry| {
+        let origin = Origin {
+            display: format!("{}:{}", path.display(), entry.name),
+            key: format!("archive:{}!{}", canonical.display(), entry.name),
+            provenance: Some(serde_json::json!({
+                "archive": canonical.display().to_string(),
+                "entry": entry.name,
+            })),
+            date: date.clone(),
+        };
+
+        let inner = Path::new(&entry.name);
+        let result = entry.content.and_then(|content| {
+            let format = opts
+                .format_override
+                .or_else(|| Format::detect_from_extension(inner))
+                .with_context(|| format!("Cannot determine format for: {}", origin.display))?;
+            let filename = inner
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown");
+            content_records(&content, filename, format, &origin, opts)
+        });
+
+        let entry_records = match result {
+            Ok(entry_records) => entry_records,
+            Err(e) if opts.keep_going => vec![Record {
+                origin: origin.display,
+                path_keyed: false,
+                transcript: Err(e),
+            }],
+            Err(e) => return Err(e),
+        };
+        entries.push((entry.name, entry_records));
+        Ok(())
+    })?;
+
+    // Tar entries arrive in archive order; report them by name like zips
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries.into_iter().flat_map(|(_, records)| records).collect())
+}
+
+/// Where a piece of content came from.
+struct Origin {
+    /// Shown in reports and logs
+    display: String,
+    /// Hashed into a stable ID for transcripts that don't carry one
+    key: String,
+    /// Merged into transcript metadata as `provenance`
+    provenance: Option<serde_json::Value>,
+    /// Date for formats that carry none: the file's mtime, or empty
+    date: String,
+}
+
+/// Parse the content of one file (or archive entry), which may hold a single
+/// transcript or many records.
+fn content_records(
+    content: &[u8],
+    filename: &str,
+    format: Format,
+    origin: &Origin,
+    opts: &IngestOptions,
+) -> Result<Vec<Record>> {
+    let text = if format.is_binary() {
+        None
+    } else {
+        let text = std::str::from_utf8(content)
+            .with_context(|| format!("Not valid UTF-8 text: {}", origin.display))?;
+        parse_records(text, filename, &origin.date, format, opts)?
+    };
+
+    if let Some(records) = text {
+        return Ok(records
+            .into_iter()
+            .map(|r| {
+                let key = match &r.key {
+                    Some(key) => format!("{}#id={key}", origin.key),
+                    None => format!("{}#{}", origin.key, r.number),
+                };
+                let path_keyed = r.transcript.as_ref().is_ok_and(|t| t.id.is_empty());
+                Record {
+                    origin: format!("{}#{}", origin.display, r.number),
+                    path_keyed,
+                    transcript: r.transcript.map(|t| {
+                        let t = with_filename_hints(t, filename, &origin.date, opts);
+                        let t = with_tags(t, &opts.tags);
+                        let t = with_identity(t, stable_id(&key), content_hash(&r.raw));
+                        with_provenance(t, origin.provenance.as_ref())
+                    }),
+                }
+            })
+            .collect());
+    }
+
+    let transcript = parse_bytes(content, filename, &origin.date, format, opts.default_source)?;
+    let transcript = with_filename_hints(transcript, filename, &origin.date, opts);
+    let transcript = with_tags(transcript, &opts.tags);
+    let path_keyed = transcript.id.is_empty();
+    let transcript = with_identity(transcript, stable_id(&origin.key), content_hash(content));
+    let transcript = with_provenance(transcript, origin.provenance.as_ref());
+
+    Ok(vec![Record {
+        origin: origin.display.clone(),
+        path_keyed,
+        transcript: Ok(transcript),
+    }])
+}
+
+/// Use a date found in the file name when the content has none of its own
+/// (a date that is just `file_date`, the mtime, loses to the name), and strip
+/// that date from a title that was derived from the file name.
+fn with_filename_hints(
+    mut transcript: NewTranscript,
+    filename: &str,
+    file_date: &str,
+    opts: &IngestOptions,
+) -> NewTranscript {
+    let Some(info) = opts.filename_patterns.infer(filename) else {
+        return transcript;
+    };
+    if transcript.date.is_empty() || transcript.date == file_date {
+        transcript.date = info.date;
+    }
+    if !info.title.is_empty() && transcript.title == markdown::filename_to_title(filename) {
+        transcript.title = info.title;
+    }
+    transcript
+}
+
+/// Add run- or rule-level tags the transcript doesn't already have.
+fn with_tags(mut transcript: NewTranscript, tags: &[String]) -> NewTranscript {
+    for tag in tags {
+        if !transcript.tags.contains(tag) {
+            transcript.tags.push(tag.clone());
+        }
+    }
+    transcript
+}
+
+/// Record where a transcript came from in its metadata.
+fn with_provenance(
+    mut transcript: NewTranscript,
+    provenance: Option<&serde_json::Value>,
+) -> NewTranscript {
+    let Some(provenance) = provenance else {
+        return transcript;
+    };
+    let meta = transcript
+        .metadata
+        .get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+    if let serde_json::Value::Object(map) = meta {
+        map.insert("provenance".into(), provenance.clone());
+    }
+    transcript
+}
+
+/// Fill in a derived ID (when the source had none) and the content hash.
+fn with_identity(mut transcript: NewTranscript, fallback_id: String, hash: String) -> NewTranscript {
+    if transcript.id.is_empty() {
+        transcript.id = fallback_id;
+    }
+    transcript.content_hash = Some(hash);
+    transcript
+}
+
+/// One transcript from a multi-record input (JSON array, NDJSON, grouped CSV).
+pub struct ParsedRecord {
+    /// 1-based record number: array position, NDJSON line, or CSV group
+    pub number: usize,
+    /// The record's own source text, used for its content hash
+    pub raw: String,
+    /// Identifies the record within its input when the transcript has no ID
+    /// of its own: a CSV `meeting_id`, or a JSON record's content hash. The
+    /// record number is used when there is none
+    pub key: Option<String>,
+    pub transcript: Result<NewTranscript>,
+}
+
+/// A parsed record from a multi-transcript input, waiting to be stored.
+struct Record {
+    /// File path or `stdin`, with `#N` for the record number
+    origin: String,
+    path_keyed: bool,
+    transcript: Result<NewTranscript>,
+}
+
+/// Split JSON arrays, NDJSON and segment tables into records. Returns `None`
+/// for inputs that hold a single transcript.
+fn parse_records(
+    content: &str,
+    filename: &str,
+    date: &str,
+    format: Format,
+    opts: &IngestOptions,
+) -> Result<Option<Vec<ParsedRecord>>> {
+    let default_source = opts.default_source;
+    match format {
+        Format::Csv | Format::Tsv => {
+            let delimiter = if format == Format::Tsv { b'\t' } else { b',' };
+            csv::parse_csv(content, filename, date, delimiter, opts.columns.as_ref(), default_source)
+                .map(Some)
+        }
+        Format::Ndjson => Ok(Some(json::parse_ndjson(content, filename, default_source))),
+        Format::Json if json::is_json_array(content) => {
+            json::parse_json_array(content, filename, default_source).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Stores parsed records on the calling thread. Each record is classified
+/// against the database as it arrives; new and changed transcripts are
+/// committed `BATCH_SIZE` per transaction, and every record is reported in
+/// arrival order when its batch is flushed.
+///
+/// Unchanged re-ingests are skipped. Changed content replaces the stored
+/// transcript when it is path-keyed or `update` is set, and is otherwise
+/// skipped as a duplicate, as is a repeated ID within one run.
+struct Writer<'a> {
+    db: &'a Database,
+    opts: &'a IngestOptions<'a>,
+    /// IDs already seen in this run
+    seen: HashSet<String>,
+    /// Records since the last flush, in arrival order
+    queue: Vec<(String, Queued)>,
+    /// Transcripts to write, referenced by `Queued::Write`
+    batch: Vec<NewTranscript>,
+}
+
+enum Queued {
+    Failed(anyhow::Error),
+    /// Reported without writing: `None` for a duplicate, otherwise an
+    /// unchanged or dry-run outcome
+    Skipped(Box<NewTranscript>, Option<UpsertOutcome>),
+    /// Index into `Writer::batch`
+    Write(usize),
+}
+
+impl<'a> Writer<'a> {
+    fn new(db: &'a Database, opts: &'a IngestOptions<'a>) -> Self {
+        Writer {
+            db,
+            opts,
+            seen: HashSet::new(),
+            queue: Vec::new(),
+            batch: Vec::new(),
+        }
+    }
+
+    /// Queue the records parsed from one file. A file that failed to parse is
+    /// recorded with `keep_going`; otherwise everything before it is flushed
+    /// and the error ends the run.
+    fn add_file(&mut self, path: &Path, parsed: Result<Vec<Record>>, stats: &mut IngestStats) -> Result<()> {
+        match parsed {
+            Ok(records) => {
+                for record in records {
+                    self.add(record, stats)?;
+                }
+                Ok(())
+            }
+            Err(e) if self.opts.keep_going => {
+                info!("Failed to ingest {}: {e:#}", path.display());
+                self.queue.push((path.display().to_string(), Queued::Failed(e)));
+                Ok(())
+            }
+            Err(e) => {
+                self.flush(stats)?;
+                Err(e)
+            }
+        }
+    }
+
+    fn add(&mut self, record: Record, stats: &mut IngestStats) -> Result<()> {
+        let queued = match record.transcript {
+            Err(e) => {
+                info!("Failed to parse {}: {e:#}", record.origin);
+                Queued::Failed(e)
+            }
+            Ok(transcript) => {
+                let repeated = !self.seen.insert(transcript.id.clone());
+                let outcome = if repeated && !self.opts.update {
+                    None
+                } else {
+                    classify(self.db, &transcript, record.path_keyed, self.opts)?
+                };
+                match outcome {
+                    Some(UpsertOutcome::Inserted | UpsertOutcome::Updated) if !self.opts.dry_run => {
+                        self.batch.push(transcript);
+                        Queued::Write(self.batch.len() - 1)
+                    }
+                    outcome => Queued::Skipped(Box::new(transcript), outcome),
+                }
+            }
+        };
+        self.queue.push((record.origin, queued));
+
+        if self.queue.len() >= BATCH_SIZE {
+            self.flush(stats)?;
+        }
+        Ok(())
+    }
+
+    /// Write the pending batch in one transaction and report every queued
+    /// record. A record that fails to write is rolled back on its own.
+    fn flush(&mut self, stats: &mut IngestStats) -> Result<()> {
+        let mut results: Vec<_> = if self.batch.is_empty() {
+            Vec::new()
+        } else {
+            self.db.upsert_batch(&self.batch)?.into_iter().map(Some).collect()
+        };
+
+        for (origin, queued) in self.queue.drain(..) {
+            match queued {
+                Queued::Failed(e) => stats.record_failure(&origin, &e),
+                Queued::Skipped(transcript, None) => {
+                    info!("Skipping duplicate: {} ({})", transcript.title, transcript.id);
+                    stats.record(&origin, &transcript, FileStatus::Duplicate);
+                }
+                Queued::Skipped(transcript, Some(outcome)) => {
+                    report(&transcript, &origin, outcome, self.opts);
+                    stats.record(&origin, &transcript, outcome.into());
+                }
+                Queued::Write(i) => {
+                    let transcript = &self.batch[i];
+                    match results[i].take().expect("one result per batched transcript") {
+                        Ok(outcome) => {
+                            report(transcript, &origin, outcome, self.opts);
+                            stats.record(&origin, transcript, outcome.into());
+                        }
+                        Err(e) => stats.record_failure(&origin, &e),
+                    }
+                }
+            }
+        }
+
+        self.batch.clear();
+        Ok(())
+    }
+}
+
+/// Decide what storing `transcript` would do, without writing. `None` means
+/// it is a duplicate that should be skipped.
+fn classify(
+    db: &Database,
+    transcript: &NewTranscript,
+    path_keyed: bool,
+    opts: &IngestOptions,
+) -> Result<Option<UpsertOutcome>> {
+    Ok(match db.get_content_hash(&transcript.id)? {
+        None => Some(UpsertOutcome::Inserted),
+        Some(Some(ref hash)) if Some(hash) == transcript.content_hash.as_ref() => {
+            Some(UpsertOutcome::Unchanged)
+        }
+        Some(_) if path_keyed || opts.update => Some(UpsertOutcome::Updated),
+        Some(_) => None,
+    })
+}
 
-    if dry_run {
+/// Per-transcript console output for dry runs and `--update`.
+fn report(transcript: &NewTranscript, origin: &str, outcome: UpsertOutcome, opts: &IngestOptions) {
+    if opts.dry_run {
+        let verb = match outcome {
+            UpsertOutcome::Inserted => "ingest",
+            UpsertOutcome::Updated => "update",
+            UpsertOutcome::Unchanged => return,
+        };
         println!(
-            "  [dry-run] Would ingest: {} ({}, {} segments)",
+            "  [dry-run] Would {verb}: {} ({}, {} segments)",
             transcript.title,
             transcript.source,
             transcript.segments.len()
         );
-        return Ok(1);
+        return;
     }
 
-    if db.transcript_exists(&transcript.id)? {
-        info!("Skipping duplicate: {} ({})", transcript.title, transcript.id);
-        return Ok(0);
+    if opts.update {
+        let label = FileStatus::from(outcome).as_str();
+        println!("  {label:<9} {} ({})", transcript.title, transcript.id);
     }
+    info!("Ingested ({:?}): {} ({})", outcome, transcript.title, origin);
+}
+
+/// SHA-256 of file content, hex-encoded.
+pub fn content_hash(content: impl AsRef<[u8]>) -> String {
+    let digest = Sha256::digest(content.as_ref());
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// A file's mtime as an RFC 3339 date, or empty if it can't be read. Used as
+/// the date of transcripts whose format doesn't record one.
+fn modified_date(path: &Path) -> String {
+    std::fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .map(|t| {
+            let dt: chrono::DateTime<chrono::Utc> = t.into();
+            dt.to_rfc3339()
+        })
+        .unwrap_or_default()
+}
 
-    db.insert_transcript(&transcript)?;
-    info!("Ingested: {} ({})", transcript.title, path.display());
-    Ok(1)
+/// Deterministic transcript ID derived from a key (path or content hash).
+fn stable_id(key: &str) -> String {
+    content_hash(key)[..32].to_string()
 }
 
+/// Parse raw input: binary documents directly, everything else as UTF-8 text.
+fn parse_bytes(
+    bytes: &[u8],
+    filename: &str,
+    date: &str,
+    format: Format,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    match format {
+        Format::Docx => docx::parse_docx(bytes, filename, default_source),
+        Format::Pdf => pdf::parse_pdf(bytes, filename, default_source),
+        _ => {
+            let content = std::str::from_utf8(bytes)
+                .with_context(|| format!("Not valid UTF-8 text: {filename}"))?;
+            parse_content(content, filename, date, format, default_source)
+        }
+    }
+}
+
+/// `date` is used by formats that have no date of their own.
 fn parse_content(
     content: &str,
     filename: &str,
+    date: &str,
     format: Format,
     default_source: Option<&str>,
 ) -> Result<NewTranscript> {
     match format {
-        Format::Json => json::parse_json(content, default_source),
+        Format::Json | Format::Ndjson => json::parse_json(content, filename, default_source),
         Format::Markdown => markdown::parse_markdown(content, filename, default_source),
         Format::Text => {
-            text::parse_text(content, Path::new(filename), default_source)
+            text::parse_text(content, Path::new(filename), date, default_source)
+        }
+        Format::Vtt => vtt::parse_vtt(content, filename, date, default_source),
+        Format::Srt => srt::parse_srt(content, filename, date, default_source),
+        Format::Html => html::parse_html(content, filename, default_source),
+        Format::Csv | Format::Tsv => unreachable!("segment tables are parsed by parse_records"),
+        Format::Docx | Format::Pdf => {
+            bail!("{format:?} documents are binary and can't be parsed from text: {filename}")
         }
     }
 }
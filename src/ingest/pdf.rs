@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+use std::cell::Cell;
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+use crate::db::models::NewTranscript;
+use crate::ingest::docx::DocumentProperties;
+use crate::ingest::markdown::filename_to_title;
+use crate::ingest::text::text_to_transcript;
+
+/// Parse a text-based PDF. Extracted text goes through the plain-text speaker
+/// detection; title, author and creation date come from the document info
+/// dictionary. Scanned (image-only) PDFs yield no text and are rejected.
+pub fn parse_pdf(bytes: &[u8], filename: &str, default_source: Option<&str>) -> Result<NewTranscript> {
+    // pdf-extract panics on many malformed and encrypted files; that has to
+    // stay a per-file failure rather than take down the ingest workers
+    let extracted = quietly(|| pdf_extract::extract_text_from_mem(bytes))
+    .map_err(|panic| {
+        let reason = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown error".to_string());
+        anyhow!("Failed to extract PDF text from {filename}: {reason}")
+    })?;
+    let text = extracted.map_err(|e| anyhow!("Failed to extract PDF text from {filename}: {e}"))?;
+    let text = clean_text(&text);
+    if text.is_empty() {
+        return Err(anyhow!("PDF has no extractable text (scanned?): {filename}"));
+    }
+
+    let props = quietly(|| info_properties(bytes)).unwrap_or_default();
+
+    Ok(text_to_transcript(
+        &text,
+        props.title.clone().unwrap_or_else(|| filename_to_title(filename)),
+        props.created.clone().unwrap_or_default(),
+        default_source.unwrap_or("pdf"),
+        Some(props.metadata("pdf")),
+    ))
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f`, catching a panic instead of unwinding, and without the default
+/// hook's "thread panicked" message: the panic is reported as a file failure.
+fn quietly<T>(f: impl FnOnce() -> T) -> std::thread::Result<T> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                default(info);
+            }
+        }));
+    });
+
+    QUIET.with(|q| q.set(true));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|q| q.set(false));
+    result
+}
+
+/// Turn page breaks into newlines, trim line ends and collapse the runs of
+/// blank lines PDF extraction leaves between blocks.
+fn clean_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank_run = 0;
+    for line in text.replace('\u{c}', "\n").lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+/// Title, Author and CreationDate from the trailer's `/Info` dictionary.
+/// Missing or unreadable entries are left empty.
+fn info_properties(bytes: &[u8]) -> DocumentProperties {
+    let mut props = DocumentProperties::default();
+    let Ok(doc) = lopdf::Document::load_mem(bytes) else {
+        return props;
+    };
+    let Some(info) = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|o| doc.dereference(o).ok())
+        .and_then(|(_, o)| o.as_dict().ok())
+    else {
+        return props;
+    };
+
+    let text = |key: &[u8]| {
+        info.get(key)
+            .ok()
+            .and_then(|o| lopdf::decode_text_string(o).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    props.title = text(b"Title");
+    props.author = text(b"Author");
+    props.created = text(b"CreationDate").and_then(|d| pdf_date(&d));
+    props
+}
+
+/// Convert a PDF date (`D:YYYYMMDDHHmmSS+HH'mm'`) to RFC 3339. Missing
+/// trailing fields default to their minimum; no zone means UTC.
+fn pdf_date(s: &str) -> Option<String> {
+    let s = s.strip_prefix("D:").unwrap_or(s);
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() < 4 {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>, default: &str| {
+        digits.get(range).unwrap_or(default).to_string()
+    };
+    let (year, month, day) = (field(0..4, ""), field(4..6, "01"), field(6..8, "01"));
+    let (hour, minute, second) = (field(8..10, "00"), field(10..12, "00"), field(12..14, "00"));
+
+    let zone = &s[digits.len()..];
+    let offset = match zone.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let parts: Vec<&str> = zone[1..].split('\'').filter(|p| !p.is_empty()).collect();
+            let hh = parts.first().copied().unwrap_or("00");
+            let mm = parts.get(1).copied().unwrap_or("00");
+            format!("{sign}{hh:0>2}:{mm:0>2}")
+        }
+        _ => "Z".to_string(),
+    };
+
+    Some(format!("{year}-{month}-{day}T{hour}:{minute}:{second}{offset}"))
+}
@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::filename_to_title;
+use crate::ingest::whisper::word_timing;
+
+/// Rev.ai / Rev.com transcript JSON (`application/vnd.rev.transcript.v1.0+json`).
+#[derive(Debug, Deserialize)]
+struct RevTranscript {
+    monologues: Vec<RevMonologue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RevMonologue {
+    speaker: Option<i64>,
+    speaker_name: Option<String>,
+    elements: Vec<RevElement>,
+}
+
+/// A `text` element is a word with timing; `punct` elements carry spacing and punctuation.
+#[derive(Debug, Deserialize)]
+struct RevElement {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+    ts: Option<f64>,
+    end_ts: Option<f64>,
+    confidence: Option<f64>,
+}
+
+/// True if a parsed JSON document looks like a Rev transcript.
+pub fn is_rev(value: &serde_json::Value) -> bool {
+    value.get("monologues").is_some_and(|m| m.is_array())
+}
+
+/// Convert Rev JSON into a NewTranscript, one segment per monologue.
+pub fn parse_rev(
+    value: serde_json::Value,
+    filename: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    let rt: RevTranscript =
+        serde_json::from_value(value).context("Failed to parse Rev transcript")?;
+
+    let mut speakers = Vec::new();
+    let mut seen = HashSet::new();
+    let mut raw_lines = Vec::new();
+    let mut segments = Vec::new();
+
+    for mono in rt.monologues {
+        let text: String = mono.elements.iter().map(|e| e.value.as_str()).collect();
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+
+        let speaker = mono
+            .speaker_name
+            .filter(|n| !n.is_empty())
+            .or_else(|| mono.speaker.map(|s| format!("Speaker {s}")))
+            .unwrap_or_default();
+        if !speaker.is_empty() && seen.insert(speaker.clone()) {
+            speakers.push(speaker.clone());
+        }
+        raw_lines.push(format!("{speaker}: {text}"));
+
+        let timed: Vec<&RevElement> = mono
+            .elements
+            .iter()
+            .filter(|e| e.kind == "text")
+            .collect();
+        let start_time = timed.iter().find_map(|e| e.ts).unwrap_or(0.0);
+        let end_time = timed.iter().rev().find_map(|e| e.end_ts).unwrap_or(start_time);
+        let words: Vec<serde_json::Value> = timed
+            .iter()
+            .map(|e| word_timing(&e.value, e.ts, e.end_ts, "confidence", e.confidence))
+            .collect();
+
+        segments.push(NewSegment {
+            speaker,
+            text,
+            start_time,
+            end_time,
+            segment_index: segments.len() as i64,
+            metadata: if words.is_empty() {
+                None
+            } else {
+                Some(serde_json::json!({ "words": words }))
+            },
+        });
+    }
+
+    let duration_seconds = segments.iter().map(|s| s.end_time).fold(0.0, f64::max);
+
+    Ok(NewTranscript {
+        id: String::new(),
+        title: filename_to_title(filename),
+        date: String::new(),
+        duration_seconds,
+        source: default_source.unwrap_or("rev").to_string(),
+        summary: String::new(),
+        raw_text: raw_lines.join("\n"),
+        metadata: Some(serde_json::json!({ "asr": "rev" })),
+        content_hash: None,
+        speakers,
+        segments,
+        tags: Vec::new(),
+        keywords: Vec::new(),
+        action_items: Vec::new(),
+    })
+}
@@ -0,0 +1,118 @@
+use anyhow::{bail, Context, Result};
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use std::path::Path;
+
+use crate::config::IngestRule;
+use crate::ingest::Format;
+
+/// `*` and `?` stay within one path component; `**` crosses directories.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Compiled `[[ingest.rules]]`, routing files to a source, tags and format
+/// by path glob and/or filename regex.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    path: Option<Pattern>,
+    filename: Option<Regex>,
+    source: Option<String>,
+    tags: Vec<String>,
+    format: Option<Format>,
+}
+
+/// What the matching rules say about one file.
+#[derive(Debug, Default)]
+pub struct Route<'r> {
+    pub source: Option<&'r str>,
+    pub format: Option<Format>,
+    pub tags: Vec<String>,
+}
+
+impl Rules {
+    pub fn new(rules: &[IngestRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(i, r)| compile(r).with_context(|| format!("Invalid [[ingest.rules]] entry {}", i + 1)))
+            .collect::<Result<_>>()?;
+        Ok(Rules { rules })
+    }
+
+    /// Apply every rule matching `path`, in config order. The first rule to
+    /// set a source or format wins; tags from all matching rules add up.
+    pub fn route(&self, path: &Path) -> Route<'_> {
+        let mut route = Route::default();
+        if self.rules.is_empty() {
+            return route;
+        }
+
+        let full = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+        for rule in &self.rules {
+            let path_ok = rule
+                .path
+                .as_ref()
+                .is_none_or(|p| p.matches_path_with(&full, MATCH_OPTIONS));
+            let name_ok = rule.filename.as_ref().is_none_or(|re| re.is_match(filename));
+            if !(path_ok && name_ok) {
+                continue;
+            }
+            route.source = route.source.or(rule.source.as_deref());
+            route.format = route.format.or(rule.format);
+            for tag in &rule.tags {
+                if !route.tags.contains(tag) {
+                    route.tags.push(tag.clone());
+                }
+            }
+        }
+        route
+    }
+}
+
+fn compile(rule: &IngestRule) -> Result<Rule> {
+    if rule.path.is_none() && rule.filename.is_none() {
+        bail!("a rule needs `path` (glob) or `filename` (regex)");
+    }
+
+    // Relative globs match anywhere below the ingested paths
+    let path = rule
+        .path
+        .as_deref()
+        .map(|glob| {
+            let glob = if glob.starts_with('/') || glob.starts_with("**") {
+                glob.to_string()
+            } else {
+                format!("**/{}", glob.trim_start_matches("./"))
+            };
+            Pattern::new(&glob).with_context(|| format!("bad path glob: {glob}"))
+        })
+        .transpose()?;
+    let filename = rule
+        .filename
+        .as_deref()
+        .map(|re| Regex::new(re).with_context(|| format!("bad filename regex: {re}")))
+        .transpose()?;
+    let format = rule
+        .format
+        .as_deref()
+        .map(|f| Format::from_str(f).with_context(|| format!("unknown format: {f}")))
+        .transpose()?;
+
+    Ok(Rule {
+        path,
+        filename,
+        source: rule.source.clone(),
+        tags: rule.tags.clone(),
+        format,
+    })
+}
@@ -0,0 +1,98 @@
+use anyhow::{bail, Result};
+
+use crate::db::models::NewTranscript;
+use crate::ingest::markdown::parse_timestamp;
+use crate::ingest::vtt::{cues_to_transcript, is_timestamp, split_speaker_prefix, Cue};
+
+/// Parse a SubRip (.srt) subtitle file into a NewTranscript.
+///
+/// Expected format:
+/// ```text
+/// 1
+/// 00:00:01,000 --> 00:00:04,500
+/// ALICE: Let's get started.
+///
+/// 2
+/// 00:00:05,000 --> 00:00:07,200
+/// Sounds good.
+/// ```
+///
+/// An optional `SPEAKER:` prefix on the first cue line fills the segment
+/// speaker. SRT has no date, so `date` (the file's mtime) is used.
+pub fn parse_srt(
+    content: &str,
+    filename: &str,
+    date: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let tag_re = regex::Regex::new(r"</?[a-zA-Z][^>]*>|\{\\[^}]*\}").unwrap();
+
+    let mut cues = Vec::new();
+
+    for block in content.split("\n\n") {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+
+        // The numeric counter is optional in practice; locate the timing line
+        let Some(timing_pos) = lines.iter().position(|l| l.contains("-->")) else {
+            continue;
+        };
+        let Some((start_time, end_time)) = parse_cue_timing(lines[timing_pos]) else {
+            continue;
+        };
+
+        // Strip <i>/<b>/<font> tags and {\an8}-style positioning codes
+        let text = lines[timing_pos + 1..]
+            .iter()
+            .map(|l| tag_re.replace_all(l.trim(), "").trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if text.is_empty() {
+            continue;
+        }
+
+        let (speaker, text) = split_speaker_prefix(&text)
+            .map(|(s, t)| (s.to_string(), t.to_string()))
+            .unwrap_or((String::new(), text));
+
+        cues.push(Cue {
+            speaker,
+            text,
+            start_time,
+            end_time,
+        });
+    }
+
+    if cues.is_empty() && !content.trim().is_empty() {
+        bail!("No SRT cues found in: {filename}");
+    }
+
+    Ok(cues_to_transcript(cues, filename, date, default_source.unwrap_or("srt")))
+}
+
+/// Parse an SRT timing line: `00:00:01,000 --> 00:00:04,500`.
+fn parse_cue_timing(line: &str) -> Option<(f64, f64)> {
+    let (start, end) = line.split_once("-->")?;
+    let start = start.trim().replace(',', ".");
+    let end = end.split_whitespace().next()?.replace(',', ".");
+    if !is_timestamp(&start) || !is_timestamp(&end) {
+        return None;
+    }
+    Some((parse_timestamp(&start), parse_timestamp(&end)))
+}
+
+/// True if the content looks like SRT: a cue counter followed by a timing line.
+pub fn looks_like_srt(content: &str) -> bool {
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(counter), Some(timing)) => {
+            counter.chars().all(|c| c.is_ascii_digit()) && parse_cue_timing(timing).is_some()
+        }
+        _ => false,
+    }
+}
@@ -2,53 +2,236 @@ use anyhow::Result;
 use std::path::Path;
 
 use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::parse_timestamp;
+
+/// Minimum number of speaker lines before a file is treated as a transcript
+/// rather than prose that happens to contain a colon.
+const MIN_SPEAKER_LINES: usize = 2;
 
 /// Parse a plain text file into a NewTranscript.
-/// Title from filename, date from mtime, body = raw_text, single segment.
-pub fn parse_text(content: &str, filepath: &Path, default_source: Option<&str>) -> Result<NewTranscript> {
+/// Title from filename; `date` is the file's mtime. Speaker lines (`Alice: ...`,
+/// `[00:01:23] Bob: ...`, Otter-style `Alice  0:03` headings) become segments;
+/// otherwise the body is stored as a single segment.
+pub fn parse_text(
+    content: &str,
+    filepath: &Path,
+    date: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
     let title = filepath
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("Untitled")
         .replace(['-', '_'], " ");
 
-    let date = std::fs::metadata(filepath)
-        .ok()
-        .and_then(|m| m.modified().ok())
-        .map(|t| {
-            let dt: chrono::DateTime<chrono::Utc> = t.into();
-            dt.to_rfc3339()
-        })
-        .unwrap_or_default();
+    let source = default_source.unwrap_or("text");
+
+    Ok(text_to_transcript(content, title, date.to_string(), source, None))
+}
 
-    let source = default_source.unwrap_or("text").to_string();
+/// Build a transcript from extracted text, splitting speaker lines into
+/// segments. Shared by plain text and the document formats (DOCX, PDF).
+pub(crate) fn text_to_transcript(
+    content: &str,
+    title: String,
+    date: String,
+    source: &str,
+    metadata: Option<serde_json::Value>,
+) -> NewTranscript {
     let raw_text = content.trim().to_string();
 
-    let segments = if raw_text.is_empty() {
-        Vec::new()
-    } else {
+    let segments = parse_speaker_lines(&raw_text);
+    let segments = if segments.is_empty() && !raw_text.is_empty() {
         vec![NewSegment {
             speaker: String::new(),
             text: raw_text.clone(),
             start_time: 0.0,
             end_time: 0.0,
             segment_index: 0,
+            metadata: None,
         }]
+    } else {
+        segments
     };
 
-    Ok(NewTranscript {
-        id: uuid::Uuid::new_v4().to_string(),
+    let mut speakers = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for seg in &segments {
+        if !seg.speaker.is_empty() && seen.insert(seg.speaker.clone()) {
+            speakers.push(seg.speaker.clone());
+        }
+    }
+
+    NewTranscript {
+        id: String::new(),
         title,
         date,
         duration_seconds: 0.0,
-        source,
+        source: source.to_string(),
         summary: String::new(),
         raw_text,
-        metadata: None,
-        speakers: Vec::new(),
+        metadata,
+        content_hash: None,
+        speakers,
         segments,
         tags: Vec::new(),
         keywords: Vec::new(),
         action_items: Vec::new(),
-    })
+    }
+}
+
+/// Split transcript-style text into speaker segments.
+///
+/// Recognised layouts:
+/// ```text
+/// Alice: Let's get started.               (inline speaker)
+/// [00:01:23] Bob: Sounds good.            (timestamp, then speaker)
+/// Bob (01:23): Sounds good.               (speaker, then timestamp)
+/// Alice Smith  0:03                       (Otter heading, text on following lines)
+/// [10:02 AM] Alice Smith                  (Teams heading, text on following lines)
+/// ```
+///
+/// Lines that match no pattern continue the current segment. Returns an empty
+/// Vec when fewer than two speaker lines are found.
+pub fn parse_speaker_lines(body: &str) -> Vec<NewSegment> {
+    const TS: &str = r"\d{1,2}:\d{2}(?::\d{2})?(?:[.,]\d+)?(?:\s?[AaPp][Mm])?";
+    const NAME: &str = r"\p{L}[\p{L}\p{N}.'’\- ]{0,39}?";
+
+    // [00:01:23] Bob: text  |  Bob (01:23): text  |  Alice: text
+    let inline_re = regex::Regex::new(&format!(
+        r"^[\[(]?({TS})?[\])]?\s*(?:[-–|]\s*)?({NAME})\s*(?:[\[(]({TS})[\])])?\s*:\s+(\S.*)$"
+    ))
+    .unwrap();
+    // Alice Smith  0:03  |  Alice Smith (0:03)
+    let name_first_re =
+        regex::Regex::new(&format!(r"^({NAME})\s+[\[(]?({TS})[\])]?$")).unwrap();
+    // [10:02 AM] Alice Smith  |  00:01:23 Alice
+    let ts_first_re =
+        regex::Regex::new(&format!(r"^[\[(]?({TS})[\])]?\s*(?:[-–|]\s*)?({NAME})$")).unwrap();
+
+    struct Turn {
+        speaker: String,
+        timestamp: Option<String>,
+        lines: Vec<String>,
+    }
+
+    let mut turns: Vec<Turn> = Vec::new();
+    let mut preamble: Vec<String> = Vec::new();
+    let mut speaker_lines = 0;
+
+    for line in body.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let matched = if let Some(cap) = inline_re.captures(line) {
+            let ts = cap.get(1).or(cap.get(3)).map(|m| m.as_str().to_string());
+            Some((cap[2].trim().to_string(), ts, Some(cap[4].trim().to_string())))
+        } else if let Some(cap) = name_first_re.captures(line) {
+            Some((cap[1].trim().to_string(), Some(cap[2].to_string()), None))
+        } else {
+            ts_first_re
+                .captures(line)
+                .map(|cap| (cap[2].trim().to_string(), Some(cap[1].to_string()), None))
+        };
+
+        match matched {
+            Some((speaker, timestamp, text)) if is_speaker_name(&speaker) => {
+                speaker_lines += 1;
+                turns.push(Turn {
+                    speaker,
+                    timestamp,
+                    lines: text.into_iter().collect(),
+                });
+            }
+            _ => match turns.last_mut() {
+                Some(turn) => turn.lines.push(line.to_string()),
+                None => preamble.push(line.to_string()),
+            },
+        }
+    }
+
+    if speaker_lines < MIN_SPEAKER_LINES {
+        return Vec::new();
+    }
+
+    // Wall-clock timestamps (Teams "10:02 AM") are rebased to the first one
+    let times: Vec<Option<f64>> = turns
+        .iter()
+        .map(|t| t.timestamp.as_deref().map(parse_line_timestamp))
+        .collect();
+    let is_clock = turns
+        .iter()
+        .any(|t| t.timestamp.as_deref().is_some_and(has_meridiem));
+    let base = if is_clock {
+        times.iter().flatten().copied().next().unwrap_or(0.0)
+    } else {
+        0.0
+    };
+
+    let mut segments = Vec::new();
+    if !preamble.is_empty() {
+        segments.push(NewSegment {
+            speaker: String::new(),
+            text: preamble.join("\n"),
+            start_time: 0.0,
+            end_time: 0.0,
+            segment_index: 0,
+            metadata: None,
+        });
+    }
+
+    for (turn, time) in turns.into_iter().zip(times) {
+        let text = turn.lines.join("\n");
+        if text.is_empty() {
+            continue;
+        }
+        segments.push(NewSegment {
+            speaker: turn.speaker,
+            text,
+            start_time: time.map(|t| (t - base).max(0.0)).unwrap_or(0.0),
+            end_time: 0.0,
+            segment_index: segments.len() as i64,
+            metadata: None,
+        });
+    }
+
+    // Set end_time from next segment's start_time
+    for i in 0..segments.len().saturating_sub(1) {
+        if segments[i + 1].start_time > segments[i].start_time {
+            segments[i].end_time = segments[i + 1].start_time;
+        }
+    }
+
+    segments
+}
+
+/// Speaker names are short labels: at most four words and not a bare number.
+fn is_speaker_name(name: &str) -> bool {
+    let words = name.split_whitespace().count();
+    (1..=4).contains(&words) && !name.chars().all(|c| c.is_ascii_digit())
+}
+
+fn has_meridiem(ts: &str) -> bool {
+    let lower = ts.to_ascii_lowercase();
+    lower.ends_with("am") || lower.ends_with("pm")
+}
+
+/// Parse `MM:SS`, `HH:MM:SS(.mmm)` or a wall-clock `H:MM AM` into seconds.
+fn parse_line_timestamp(ts: &str) -> f64 {
+    let lower = ts.to_ascii_lowercase();
+    let pm = lower.ends_with("pm");
+    if has_meridiem(ts) {
+        let clock = lower.trim_end_matches(['a', 'p', 'm']).trim();
+        let parts: Vec<f64> = clock.split(':').map(|p| p.parse().unwrap_or(0.0)).collect();
+        let mut hours = parts.first().copied().unwrap_or(0.0) % 12.0;
+        if pm {
+            hours += 12.0;
+        }
+        let minutes = parts.get(1).copied().unwrap_or(0.0);
+        let seconds = parts.get(2).copied().unwrap_or(0.0);
+        return hours * 3600.0 + minutes * 60.0 + seconds;
+    }
+    parse_timestamp(&ts.replace(',', "."))
 }
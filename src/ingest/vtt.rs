@@ -0,0 +1,218 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::{filename_to_title, parse_timestamp};
+
+/// `<v Name>` / `<v.class Name>` voice tags; captures the name.
+static VOICE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<v(?:\.[^\s>]+)?\s+([^>]+)>").expect("valid voice tag regex"));
+
+/// Any inline cue tag.
+static CUE_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[^>]*>").expect("valid cue tag regex"));
+
+/// A single timed cue, shared by the subtitle formats (WebVTT, SRT).
+#[derive(Debug, Clone)]
+pub(crate) struct Cue {
+    pub speaker: String,
+    pub text: String,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+/// Parse a WebVTT file (Zoom, Teams, Google Meet exports) into a NewTranscript.
+///
+/// Expected format:
+/// ```text
+/// WEBVTT
+///
+/// 1
+/// 00:00:01.000 --> 00:00:04.500
+/// <v Alice>Let's get started.</v>
+///
+/// 00:00:05.000 --> 00:00:07.200
+/// Bob: Sounds good.
+/// ```
+///
+/// Speakers come from `<v Name>` voice tags, falling back to `Name:` cue
+/// prefixes. WebVTT has no date, so `date` (the file's mtime) is used.
+pub fn parse_vtt(
+    content: &str,
+    filename: &str,
+    date: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    let content = content.trim_start_matches('\u{feff}');
+    if !content.trim_start().starts_with("WEBVTT") {
+        bail!("Not a WebVTT file (missing WEBVTT header): {filename}");
+    }
+
+    let mut cues = Vec::new();
+    let blocks = content.replace("\r\n", "\n");
+
+    // Blocks are separated by blank lines; the first block is the WEBVTT header.
+    for block in blocks.split("\n\n").skip(1) {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+        if lines.is_empty() {
+            continue;
+        }
+
+        // NOTE, STYLE and REGION blocks carry no cue text
+        let first = lines[0].trim_start();
+        if first.starts_with("NOTE") || first == "STYLE" || first == "REGION" {
+            continue;
+        }
+
+        // Optional cue identifier precedes the timing line
+        let Some(timing_pos) = lines.iter().position(|l| l.contains("-->")) else {
+            continue;
+        };
+        let Some((start_time, end_time)) = parse_cue_timing(lines[timing_pos]) else {
+            continue;
+        };
+
+        let payload = lines[timing_pos + 1..].join("\n");
+        let voice = VOICE
+            .captures(&payload)
+            .map(|c| c[1].trim().to_string());
+        let text = strip_cue_markup(&payload);
+        if text.is_empty() {
+            continue;
+        }
+
+        let (speaker, text) = match voice {
+            Some(v) => (v, text),
+            None => split_speaker_prefix(&text)
+                .map(|(s, t)| (s.to_string(), t.to_string()))
+                .unwrap_or((String::new(), text)),
+        };
+
+        cues.push(Cue {
+            speaker,
+            text,
+            start_time,
+            end_time,
+        });
+    }
+
+    Ok(cues_to_transcript(cues, filename, date, default_source.unwrap_or("vtt")))
+}
+
+/// Parse a cue timing line: `00:00:01.000 --> 00:00:04.500 [settings]`.
+fn parse_cue_timing(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    let start = start.trim();
+    if !is_timestamp(start) || !is_timestamp(end) {
+        return None;
+    }
+    Some((parse_timestamp(start), parse_timestamp(end)))
+}
+
+pub(crate) fn is_timestamp(s: &str) -> bool {
+    !s.is_empty()
+        && s.contains(':')
+        && s.chars().all(|c| c.is_ascii_digit() || c == ':' || c == '.')
+}
+
+/// Remove inline cue tags (`<v>`, `<c>`, `<i>`, `<00:00:01.000>`) and decode entities.
+fn strip_cue_markup(payload: &str) -> String {
+    let stripped = CUE_TAG.replace_all(payload, "");
+    let decoded = stripped
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&lrm;", "")
+        .replace("&rlm;", "")
+        .replace("&amp;", "&");
+    decoded
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split a `Name: text` prefix off a cue line. Names must look like a short
+/// label (at most four words, starting with a letter) so ordinary sentences
+/// containing a colon are left alone.
+pub(crate) fn split_speaker_prefix(text: &str) -> Option<(&str, &str)> {
+    let (name, rest) = text.split_once(':')?;
+    let name = name.trim();
+    let rest = rest.trim();
+    if name.is_empty() || rest.is_empty() || name.len() > 40 {
+        return None;
+    }
+    if !name.chars().next().is_some_and(|c| c.is_alphabetic()) {
+        return None;
+    }
+    if name.split_whitespace().count() > 4 {
+        return None;
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '.' | '\'' | '-' | '_'))
+    {
+        return None;
+    }
+    Some((name, rest))
+}
+
+/// Build a NewTranscript from parsed cues. Title comes from the filename and
+/// duration from the last cue's end time; subtitle formats carry no date, so
+/// the caller supplies one.
+pub(crate) fn cues_to_transcript(
+    cues: Vec<Cue>,
+    filename: &str,
+    date: &str,
+    source: &str,
+) -> NewTranscript {
+    let mut speakers = Vec::new();
+    let mut seen = HashSet::new();
+    let mut raw_lines = Vec::new();
+    let mut segments = Vec::new();
+
+    for (i, cue) in cues.into_iter().enumerate() {
+        if !cue.speaker.is_empty() && seen.insert(cue.speaker.clone()) {
+            speakers.push(cue.speaker.clone());
+        }
+        if cue.speaker.is_empty() {
+            raw_lines.push(cue.text.clone());
+        } else {
+            raw_lines.push(format!("{}: {}", cue.speaker, cue.text));
+        }
+        segments.push(NewSegment {
+            speaker: cue.speaker,
+            text: cue.text,
+            start_time: cue.start_time,
+            end_time: cue.end_time,
+            segment_index: i as i64,
+            metadata: None,
+        });
+    }
+
+    let duration_seconds = segments
+        .iter()
+        .map(|s| s.end_time)
+        .fold(0.0, f64::max);
+
+    NewTranscript {
+        id: String::new(),
+        title: filename_to_title(filename),
+        date: date.to_string(),
+        duration_seconds,
+        source: source.to_string(),
+        summary: String::new(),
+        raw_text: raw_lines.join("\n"),
+        metadata: None,
+        content_hash: None,
+        speakers,
+        segments,
+        tags: Vec::new(),
+        keywords: Vec::new(),
+        action_items: Vec::new(),
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{bail, Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::db::Database;
+use crate::ingest::{
+    ingest_file, ingest_paths, is_ingestible, FileStatus, IngestOptions, IngestStats,
+};
+
+/// How long a file must go without events (and without changing size)
+/// before it is considered fully written.
+const DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// Poll interval for the event loop; also bounds Ctrl-C latency.
+const TICK: Duration = Duration::from_millis(250);
+
+/// A file that changed recently and is waiting for writes to settle.
+struct Pending {
+    last_event: Instant,
+    size: Option<u64>,
+}
+
+/// Ingest `paths`, then keep watching them and ingest new or changed files
+/// as they appear. Runs until Ctrl-C. Files that fail, in the initial scan
+/// or later, are reported and skipped.
+pub fn watch_paths(db: &Database, paths: &[String], opts: &IngestOptions) -> Result<()> {
+    for p in paths {
+        if !Path::new(p).exists() {
+            bail!("Cannot watch missing path: {p}");
+        }
+    }
+
+    // Catch up on anything that arrived while we weren't running. A bad file
+    // already in the folder is reported, not a reason to stop watching.
+    let scan_opts = IngestOptions {
+        keep_going: true,
+        ..opts.clone()
+    };
+    let stats = ingest_paths(db, paths, &scan_opts)?;
+    for file in &stats.files {
+        if file.status == FileStatus::Failed {
+            let error = file.error.as_deref().unwrap_or_default();
+            eprintln!("  failed    {}: {error}", file.path);
+        }
+    }
+    println!(
+        "Initial scan: {} ingested, {} updated, {} unchanged, {} failed",
+        stats.ingested, stats.updated, stats.unchanged, stats.failed
+    );
+
+    let running = Arc::new(AtomicBool::new(true));
+    {
+        let running = running.clone();
+        ctrlc::set_handler(move || running.store(false, Ordering::SeqCst))
+            .context("Failed to install Ctrl-C handler")?;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    for p in paths {
+        watcher
+            .watch(Path::new(p), RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch: {p}"))?;
+    }
+    println!("Watching {} (Ctrl-C to stop)", paths.join(", "));
+
+    // Events carry absolute paths, but through a symlinked root they may or
+    // may not be resolved, so keep each root in both forms
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for p in paths.iter().map(Path::new).filter(|p| p.is_dir()) {
+        for root in [std::path::absolute(p).ok(), std::fs::canonicalize(p).ok()]
+            .into_iter()
+            .flatten()
+        {
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+    }
+    let mut pending: HashMap<PathBuf, Pending> = HashMap::new();
+
+    while running.load(Ordering::SeqCst) {
+        match rx.recv_timeout(TICK) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths {
+                        if is_candidate(&path, &roots, opts) {
+                            pending.insert(
+                                path,
+                                Pending {
+                                    last_event: Instant::now(),
+                                    size: None,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+            Ok(Err(e)) => warn!("Watch error: {e}"),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        for path in settled(&mut pending) {
+            ingest_one(db, &path, opts);
+        }
+    }
+
+    println!("Stopped watching");
+    Ok(())
+}
+
+/// Files worth ingesting: regular transcript files and archives, skipping
+/// editor temp files and anything a directory walk from `roots` would skip
+/// (hidden, `--exclude`, `.tssignore`).
+fn is_candidate(path: &Path, roots: &[PathBuf], opts: &IngestOptions) -> bool {
+    let temp = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_none_or(|n| n.ends_with('~'));
+    let resolved = resolve_parent(path);
+    let ignored = roots
+        .iter()
+        .find_map(|root| {
+            [path, resolved.as_path()]
+                .into_iter()
+                .find(|p| p.starts_with(root))
+                .map(|p| opts.ignore.is_ignored_below(root, p))
+        })
+        .unwrap_or(false);
+    !temp && !ignored && path.is_file() && is_ingestible(path, opts)
+}
+
+/// `path` with its directory canonicalized, leaving the file name itself
+/// alone so a symlinked file still matches the root it sits under.
+fn resolve_parent(path: &Path) -> PathBuf {
+    let resolved = path
+        .parent()
+        .and_then(|dir| std::fs::canonicalize(dir).ok())
+        .zip(path.file_name())
+        .map(|(dir, name)| dir.join(name));
+    resolved.unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Remove and return pending files that have been quiet for `DEBOUNCE` and
+/// whose size has stopped changing since the last check.
+fn settled(pending: &mut HashMap<PathBuf, Pending>) -> Vec<PathBuf> {
+    let now = Instant::now();
+    let mut ready = Vec::new();
+
+    pending.retain(|path, p| {
+        if now.duration_since(p.last_event) < DEBOUNCE {
+            return true;
+        }
+        let Ok(meta) = std::fs::metadata(path) else {
+            // Deleted or renamed away before it settled
+            return false;
+        };
+        let size = meta.len();
+        if p.size == Some(size) {
+            ready.push(path.clone());
+            false
+        } else {
+            // Still growing: wait another debounce window
+            p.size = Some(size);
+            p.last_event = now;
+            true
+        }
+    });
+
+    ready.sort();
+    ready
+}
+
+fn ingest_one(db: &Database, path: &Path, opts: &IngestOptions) {
+    let mut stats = IngestStats::default();
+    match ingest_file(db, path, opts, &mut stats) {
+        // Update mode already reports each transcript
+        Ok(()) if opts.update => {}
+        Ok(()) => {
+            let outcome = if stats.ingested > 0 {
+                "ingested"
+            } else if stats.updated > 0 {
+                "updated"
+            } else if stats.duplicates > 0 {
+                "duplicate"
+            } else {
+                return;
+            };
+            println!("  {outcome:<9} {}", path.display());
+        }
+        Err(e) => eprintln!("  failed    {}: {e:#}", path.display()),
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::db::models::{NewSegment, NewTranscript};
+use crate::ingest::markdown::filename_to_title;
+
+/// Whisper / faster-whisper / WhisperX JSON output.
+#[derive(Debug, Deserialize)]
+pub struct WhisperTranscript {
+    pub text: Option<String>,
+    pub language: Option<String>,
+    pub language_probability: Option<f64>,
+    pub duration: Option<f64>,
+    pub segments: Vec<WhisperSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WhisperSegment {
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    pub text: Option<String>,
+    pub avg_logprob: Option<f64>,
+    pub no_speech_prob: Option<f64>,
+    pub compression_ratio: Option<f64>,
+    /// WhisperX diarization label
+    pub speaker: Option<String>,
+    pub words: Option<Vec<WhisperWord>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WhisperWord {
+    pub word: Option<String>,
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    #[serde(alias = "score")]
+    pub probability: Option<f64>,
+}
+
+/// True if a parsed JSON document has the Whisper shape: a `segments` array
+/// carrying decoder fields (`avg_logprob`, `no_speech_prob`, `words`, `seek`).
+pub fn is_whisper(value: &serde_json::Value) -> bool {
+    if value.get("raw_text").is_some() {
+        return false;
+    }
+    value
+        .get("segments")
+        .and_then(|s| s.as_array())
+        .and_then(|s| s.first())
+        .is_some_and(|seg| {
+            ["avg_logprob", "no_speech_prob", "words", "seek"]
+                .iter()
+                .any(|k| seg.get(k).is_some())
+        })
+}
+
+/// Convert Whisper JSON into a NewTranscript. Language goes into transcript
+/// metadata; per-segment confidence and word timings into segment metadata.
+pub fn parse_whisper(
+    value: serde_json::Value,
+    filename: &str,
+    default_source: Option<&str>,
+) -> Result<NewTranscript> {
+    let wt: WhisperTranscript =
+        serde_json::from_value(value).context("Failed to parse Whisper JSON transcript")?;
+
+    let mut speakers = Vec::new();
+    let mut seen = HashSet::new();
+    let mut raw_lines = Vec::new();
+    let mut segments = Vec::new();
+
+    for seg in wt.segments {
+        let text = seg.text.as_deref().unwrap_or_default().trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        let speaker = seg.speaker.unwrap_or_default();
+        if !speaker.is_empty() && seen.insert(speaker.clone()) {
+            speakers.push(speaker.clone());
+        }
+        if speaker.is_empty() {
+            raw_lines.push(text.clone());
+        } else {
+            raw_lines.push(format!("{speaker}: {text}"));
+        }
+
+        let mut meta = serde_json::Map::new();
+        if let Some(lp) = seg.avg_logprob {
+            meta.insert("avg_logprob".into(), serde_json::json!(lp));
+            meta.insert("confidence".into(), serde_json::json!(lp.exp()));
+        }
+        if let Some(v) = seg.no_speech_prob {
+            meta.insert("no_speech_prob".into(), serde_json::json!(v));
+        }
+        if let Some(v) = seg.compression_ratio {
+            meta.insert("compression_ratio".into(), serde_json::json!(v));
+        }
+        if let Some(words) = seg.words {
+            let words: Vec<serde_json::Value> = words
+                .into_iter()
+                .filter_map(|w| {
+                    let word = w.word?;
+                    Some(word_timing(word.trim(), w.start, w.end, "probability", w.probability))
+                })
+                .collect();
+            if !words.is_empty() {
+                meta.insert("words".into(), serde_json::Value::Array(words));
+            }
+        }
+
+        segments.push(NewSegment {
+            speaker,
+            text,
+            start_time: seg.start.unwrap_or(0.0),
+            end_time: seg.end.unwrap_or(0.0),
+            segment_index: segments.len() as i64,
+            metadata: if meta.is_empty() {
+                None
+            } else {
+                Some(serde_json::Value::Object(meta))
+            },
+        });
+    }
+
+    let duration_seconds = wt
+        .duration
+        .unwrap_or_else(|| segments.iter().map(|s| s.end_time).fold(0.0, f64::max));
+
+    let raw_text = match wt.text {
+        Some(t) if !t.trim().is_empty() && speakers.is_empty() => t.trim().to_string(),
+        _ => raw_lines.join("\n"),
+    };
+
+    let mut meta = serde_json::Map::new();
+    meta.insert("asr".into(), serde_json::Value::String("whisper".into()));
+    if let Some(lang) = wt.language {
+        meta.insert("language".into(), serde_json::Value::String(lang));
+    }
+    if let Some(p) = wt.language_probability {
+        meta.insert("language_probability".into(), serde_json::json!(p));
+    }
+
+    Ok(NewTranscript {
+        id: String::new(),
+        title: filename_to_title(filename),
+        date: String::new(),
+        duration_seconds,
+        source: default_source.unwrap_or("whisper").to_string(),
+        summary: String::new(),
+        raw_text,
+        metadata: Some(serde_json::Value::Object(meta)),
+        content_hash: None,
+        speakers,
+        segments,
+        tags: Vec::new(),
+        keywords: Vec::new(),
+        action_items: Vec::new(),
+    })
+}
+
+/// One entry of a segment's `words` metadata array. Shared by the ASR adapters
+/// so every vendor stores word timings in the same shape (times in seconds).
+pub(crate) fn word_timing(
+    word: &str,
+    start: Option<f64>,
+    end: Option<f64>,
+    score_key: &str,
+    score: Option<f64>,
+) -> serde_json::Value {
+    let mut m = serde_json::Map::new();
+    m.insert("word".into(), serde_json::Value::String(word.to_string()));
+    if let Some(v) = start {
+        m.insert("start".into(), serde_json::json!(v));
+    }
+    if let Some(v) = end {
+        m.insert("end".into(), serde_json::json!(v));
+    }
+    if let Some(v) = score {
+        m.insert(score_key.into(), serde_json::json!(v));
+    }
+    serde_json::Value::Object(m)
+}
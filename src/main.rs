@@ -5,7 +5,7 @@ use tss::config;
 use tss::db::Database;
 use tss::ingest;
 use tss::output::{json as json_out, table};
-use tss::search::filters::Filters;
+use tss::search::{self, filters::Filters, ContextWindow};
 use tss::sync;
 
 #[derive(Parser)]
@@ -27,7 +27,10 @@ struct Cli {
 enum Commands {
     /// Search transcripts using full-text search
     Search {
-        /// Search query (FTS5 syntax: phrases, boolean, prefix*)
+        /// Search query (FTS5 syntax: phrases, boolean, prefix*), with optional
+        /// inline filters: speaker:, source:, tag:, after:, before: (prefix - to exclude).
+        /// With --segments, speaker: matches only what that speaker said
+        #[arg(allow_hyphen_values = true)]
         query: String,
 
         /// Filter by speaker name (partial match)
@@ -42,7 +45,7 @@ enum Commands {
         #[arg(long)]
         from: Option<String>,
 
-        /// Filter by date range end (YYYY-MM-DD)
+        /// Filter by date range end, inclusive (YYYY-MM-DD covers the whole day)
         #[arg(long)]
         to: Option<String>,
 
@@ -54,9 +57,25 @@ enum Commands {
         #[arg(long)]
         segments: bool,
 
+        /// With --segments, show N segments before and after each hit
+        #[arg(long, value_name = "N", requires = "segments", conflicts_with = "context_seconds")]
+        context: Option<usize>,
+
+        /// With --segments, show segments within S seconds of each hit
+        #[arg(long, value_name = "S", requires = "segments")]
+        context_seconds: Option<f64>,
+
         /// Maximum results to return
         #[arg(long, default_value = "20")]
         limit: usize,
+
+        /// Skip this many results (for paging)
+        #[arg(long, default_value = "0", conflicts_with = "cursor")]
+        offset: usize,
+
+        /// Continue from the `next_cursor` token of a previous page
+        #[arg(long)]
+        cursor: Option<String>,
     },
 
     /// List transcripts
@@ -69,11 +88,11 @@ enum Commands {
         #[arg(long)]
         speaker: Option<String>,
 
-        /// Filter by date range start
+        /// Filter by date range start (YYYY-MM-DD)
         #[arg(long)]
         from: Option<String>,
 
-        /// Filter by date range end
+        /// Filter by date range end, inclusive (YYYY-MM-DD covers the whole day)
         #[arg(long)]
         to: Option<String>,
 
@@ -84,6 +103,14 @@ enum Commands {
         /// Maximum results
         #[arg(long, default_value = "50")]
         limit: usize,
+
+        /// Skip this many results (for paging; `--json` then reports the page with its total)
+        #[arg(long, conflicts_with = "cursor")]
+        offset: Option<usize>,
+
+        /// Continue from the `next_cursor` token of a previous page
+        #[arg(long)]
+        cursor: Option<String>,
     },
 
     /// Show transcript details
@@ -101,9 +128,13 @@ enum Commands {
         #[arg(long)]
         speaker: Option<String>,
 
-        /// Highlight segments matching query
+        /// Highlight segments matching query (FTS5 syntax)
         #[arg(long)]
         query: Option<String>,
+
+        /// Show only matching segments plus N neighbours on each side
+        #[arg(long, value_name = "N", requires = "query")]
+        context: Option<usize>,
     },
 
     /// Ingest transcripts from files or stdin
@@ -119,13 +150,37 @@ enum Commands {
         #[arg(long)]
         source: Option<String>,
 
-        /// Force format: json, markdown, text
+        /// Force format: json, ndjson, markdown, text, vtt, srt, html, csv, tsv, docx, pdf
         #[arg(long)]
         format: Option<String>,
 
         /// Preview without importing
         #[arg(long)]
         dry_run: bool,
+
+        /// Replace transcripts whose ID already exists (segments, speakers, tags, etc.)
+        #[arg(long)]
+        update: bool,
+
+        /// Keep running and ingest new or changed files as they appear
+        #[arg(long, conflicts_with = "stdin")]
+        watch: bool,
+
+        /// Skip files that fail to parse and report them at the end (exits non-zero on failures)
+        #[arg(long)]
+        keep_going: bool,
+
+        /// CSV/TSV column mapping, e.g. "speaker,start,end,text" or "speaker=Name,text=Utterance"
+        #[arg(long)]
+        columns: Option<String>,
+
+        /// Skip paths matching a gitignore-style glob when walking directories (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+
+        /// Include hidden files and directories when walking directories
+        #[arg(long)]
+        hidden: bool,
     },
 
     /// Migrate from legacy Python transcripts.db
@@ -231,37 +286,77 @@ fn main() -> Result<()> {
             to,
             tag,
             segments,
+            context,
+            context_seconds,
             limit,
+            offset,
+            cursor,
         } => {
             let filters = Filters {
-                speaker,
-                source,
+                speakers: speaker.into_iter().collect(),
+                sources: source.into_iter().collect(),
                 from_date: from,
                 to_date: to,
-                tag,
+                tags: tag.into_iter().collect(),
+                ..Default::default()
+            };
+            let mode = if segments {
+                search::query::SearchMode::Segments
+            } else {
+                search::query::SearchMode::Transcripts
+            };
+            let inline = search::query::parse(&query, filters, mode)?;
+            let (text, filters) = (inline.text, inline.filters);
+            let kind = if segments { "segments" } else { "transcripts" };
+            let scope = format!("search\0{kind}\0{text}\0{filters:?}");
+            let offset = match &cursor {
+                Some(token) => search::cursor::decode(token, &scope)?,
+                None => offset,
             };
 
             if segments {
-                let results = db.search_segments(&query, &filters, limit)?;
+                if text.is_empty() {
+                    bail!("Segment search needs search terms besides field filters");
+                }
+                let mut page = db.search_segments(&text, &filters, limit, offset)?;
+                let window = match (context, context_seconds) {
+                    (Some(n), _) => Some(ContextWindow::Segments(n)),
+                    (None, Some(secs)) => Some(ContextWindow::Seconds(secs)),
+                    (None, None) => None,
+                };
+                if let Some(window) = window {
+                    db.attach_context(&mut page.results, window)?;
+                }
+                let next_cursor = page.next_offset().map(|o| search::cursor::encode(o, &scope));
                 if json_output {
                     json_out::print_json(&serde_json::json!({
                         "query": query,
-                        "total": results.len(),
-                        "segments": results,
+                        "total": page.total,
+                        "offset": page.offset,
+                        "next_cursor": next_cursor,
+                        "segments": page.results,
                     }))?;
                 } else {
-                    table::print_segment_results(&results, &query);
+                    table::print_segment_results(&page, &query, next_cursor.as_deref());
                 }
             } else {
-                let results = db.search_transcripts(&query, &filters, limit)?;
+                // A query of nothing but filters lists the matching transcripts
+                let page = if text.is_empty() {
+                    db.list_transcripts(&filters, "date", limit, offset)?
+                } else {
+                    db.search_transcripts(&text, &filters, limit, offset)?
+                };
+                let next_cursor = page.next_offset().map(|o| search::cursor::encode(o, &scope));
                 if json_output {
                     json_out::print_json(&serde_json::json!({
                         "query": query,
-                        "total": results.len(),
-                        "transcripts": results,
+                        "total": page.total,
+                        "offset": page.offset,
+                        "next_cursor": next_cursor,
+                        "transcripts": page.results,
                     }))?;
                 } else {
-                    table::print_transcript_results(&results, &query);
+                    table::print_transcript_results(&page, &query, next_cursor.as_deref());
                 }
             }
         }
@@ -273,19 +368,36 @@ fn main() -> Result<()> {
             to,
             sort,
             limit,
+            offset,
+            cursor,
         } => {
             let filters = Filters {
-                speaker,
-                source,
+                speakers: speaker.into_iter().collect(),
+                sources: source.into_iter().collect(),
                 from_date: from,
                 to_date: to,
-                tag: None,
+                ..Default::default()
             };
-            let results = db.list_transcripts(&filters, &sort, limit)?;
-            if json_output {
-                json_out::print_json(&results)?;
+            let scope = format!("list\0{sort}\0{filters:?}");
+            // Unpaged JSON stays a bare array, as it was before paging existed
+            let paged = offset.is_some() || cursor.is_some();
+            let offset = match &cursor {
+                Some(token) => search::cursor::decode(token, &scope)?,
+                None => offset.unwrap_or(0),
+            };
+            let page = db.list_transcripts(&filters, &sort, limit, offset)?;
+            let next_cursor = page.next_offset().map(|o| search::cursor::encode(o, &scope));
+            if json_output && !paged {
+                json_out::print_json(&page.results)?;
+            } else if json_output {
+                json_out::print_json(&serde_json::json!({
+                    "total": page.total,
+                    "offset": page.offset,
+                    "next_cursor": next_cursor,
+                    "transcripts": page.results,
+                }))?;
             } else {
-                table::print_transcript_list(&results);
+                table::print_transcript_list(&page, next_cursor.as_deref());
             }
         }
 
@@ -316,23 +428,38 @@ fn main() -> Result<()> {
         Commands::Expand {
             id,
             speaker,
-            query: _,
+            query,
+            context,
         } => {
             let t = db
                 .get_transcript(&id)?
                 .with_context(|| format!("Transcript not found: {id}"))?;
 
-            let segments = db.get_segments(&id)?;
+            let mut segments = db.get_segments(&id)?;
+            if let Some(speaker) = &speaker {
+                let lower = speaker.to_lowercase();
+                segments.retain(|s| s.speaker.to_lowercase().contains(&lower));
+            }
+            let matches = query
+                .as_deref()
+                .map(|q| db.matching_segment_ids(&id, q))
+                .transpose()?;
+            let segments = search::expand_segments(segments, matches.as_ref(), context);
 
             if json_output {
-                json_out::print_json(&serde_json::json!({
+                let mut out = serde_json::json!({
                     "transcript_id": id,
                     "title": t.title,
                     "segments": segments,
-                }))?;
+                });
+                if let Some(q) = &query {
+                    out["query"] = q.as_str().into();
+                    out["matches"] = segments.iter().filter(|s| s.matched == Some(true)).count().into();
+                }
+                json_out::print_json(&out)?;
             } else {
                 println!("Transcript: {} ({})\n", t.title, id);
-                table::print_segments(&segments, speaker.as_deref());
+                table::print_segments(&segments, query.as_deref());
             }
         }
 
@@ -342,25 +469,73 @@ fn main() -> Result<()> {
             source,
             format,
             dry_run,
+            update,
+            watch,
+            keep_going,
+            columns,
+            exclude,
+            hidden,
         } => {
             let format_enum = format
                 .as_deref()
                 .map(|f| {
                     ingest::Format::from_str(f)
-                        .with_context(|| format!("Unknown format: {f}. Use: json, markdown, text"))
+                        .with_context(|| format!("Unknown format: {f}. Use: json, ndjson, markdown, text, vtt, srt, html, csv, tsv, docx, pdf"))
                 })
                 .transpose()?;
 
-            let count = if stdin {
-                ingest::ingest_stdin(&db, source.as_deref(), format_enum, dry_run)?
+            let cfg = config::TssConfig::load()?;
+            let ingest_cfg = cfg.ingest.unwrap_or_default();
+
+            let opts = ingest::IngestOptions {
+                default_source: source.as_deref(),
+                format_override: format_enum,
+                dry_run,
+                update,
+                keep_going,
+                columns: columns
+                    .as_deref()
+                    .map(ingest::csv::ColumnMap::parse)
+                    .transpose()?,
+                filename_patterns: ingest::filename::FilenamePatterns::new(
+                    &ingest_cfg.filename_patterns,
+                )?,
+                rules: ingest::rules::Rules::new(&ingest_cfg.rules)?,
+                tags: Vec::new(),
+                ignore: ingest::ignore::Ignore::new(&exclude, hidden)?,
+            };
+
+            if watch {
+                if paths.is_empty() {
+                    bail!("No paths provided to watch.");
+                }
+                return ingest::watch::watch_paths(&db, &paths, &opts);
+            }
+
+            let stats = if stdin {
+                ingest::ingest_stdin(&db, &opts)?
             } else if paths.is_empty() {
                 bail!("No paths provided. Use --stdin to read from stdin.");
             } else {
-                ingest::ingest_paths(&db, &paths, source.as_deref(), format_enum, dry_run)?
+                ingest::ingest_paths(&db, &paths, &opts)?
             };
 
-            let action = if dry_run { "Would ingest" } else { "Ingested" };
-            println!("{action} {count} transcript{}", if count == 1 { "" } else { "s" });
+            if json_output {
+                json_out::print_json(&stats)?;
+            } else {
+                if keep_going || stats.failed > 0 {
+                    table::print_ingest_report(&stats.files);
+                }
+                print_ingest_summary(&stats, dry_run);
+            }
+
+            if stats.failed > 0 {
+                bail!(
+                    "Ingest finished with {} failure{}",
+                    stats.failed,
+                    if stats.failed == 1 { "" } else { "s" }
+                );
+            }
         }
 
         Commands::Migrate { db_path: src, dry_run } => {
@@ -559,3 +734,22 @@ fn format_bytes(bytes: u64) -> String {
         format!("{bytes} B")
     }
 }
+
+fn print_ingest_summary(stats: &ingest::IngestStats, dry_run: bool) {
+    let count = stats.ingested;
+    let action = if dry_run { "Would ingest" } else { "Ingested" };
+    print!("{action} {count} transcript{}", if count == 1 { "" } else { "s" });
+    if stats.updated > 0 {
+        print!(", {} {}", if dry_run { "would update" } else { "updated" }, stats.updated);
+    }
+    if stats.unchanged > 0 {
+        print!(", {} unchanged", stats.unchanged);
+    }
+    if stats.duplicates > 0 {
+        print!(", {} already present (use --update to replace)", stats.duplicates);
+    }
+    if stats.failed > 0 {
+        print!(", {} failed", stats.failed);
+    }
+    println!();
+}
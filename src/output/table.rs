@@ -1,7 +1,18 @@
+use std::io::IsTerminal;
 use unicode_width::UnicodeWidthStr;
 
 use crate::db::models::*;
-use crate::search::{SegmentResult, TranscriptResult};
+use crate::ingest::FileReport;
+use crate::search::{ContextSegment, ExpandedSegment, MatchRange, Page, SegmentResult, TranscriptResult};
+
+const DIM: &str = "\x1b[2m";
+const MATCH: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+/// ANSI styling only when stdout is a terminal and `NO_COLOR` is unset.
+fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
 
 /// Format duration in seconds to human-readable string.
 pub fn format_duration(seconds: f64) -> String {
@@ -37,18 +48,78 @@ fn truncate(s: &str, max_width: usize) -> String {
     result
 }
 
+/// Truncate `text` to `max_width` and, with colour on, paint the parts of it
+/// covered by `ranges` (byte offsets into `text`).
+fn paint_matches(text: &str, ranges: &[MatchRange], max_width: usize, color: bool) -> String {
+    let truncated = truncate(text, max_width);
+    if !color || ranges.is_empty() {
+        return truncated;
+    }
+    let kept = if truncated.len() < text.len() {
+        truncated.len() - 3
+    } else {
+        text.len()
+    };
+
+    let mut out = String::with_capacity(truncated.len() + ranges.len() * 12);
+    let mut pos = 0;
+    for r in ranges.iter().filter(|r| r.start < kept) {
+        let end = r.end.min(kept);
+        out.push_str(&truncated[pos..r.start]);
+        out.push_str(MATCH);
+        out.push_str(&truncated[r.start..end]);
+        out.push_str(RESET);
+        pos = end;
+    }
+    out.push_str(&truncated[pos..]);
+    out
+}
+
+/// Colour `>>>`/`<<<` snippet markers when writing to a terminal.
+fn paint_snippet(snippet: &str, color: bool) -> String {
+    if !color || !snippet.contains(">>>") {
+        return snippet.to_string();
+    }
+    format!("{}{RESET}", snippet.replace(">>>", MATCH).replace("<<<", RESET))
+}
+
+/// ` (showing 21-40)` when the page isn't the whole result set.
+fn shown_range<T>(page: &Page<T>) -> String {
+    if page.offset == 0 && page.results.len() == page.total {
+        return String::new();
+    }
+    format!(" (showing {}-{})", page.offset + 1, page.offset + page.results.len())
+}
+
+/// Explain an empty page that is only empty because of `--offset`.
+fn past_end<T>(page: &Page<T>) -> String {
+    if page.total == 0 {
+        return String::new();
+    }
+    format!(" at offset {} ({} in total)", page.offset, page.total)
+}
+
+/// How to ask for the next page, if there is one.
+fn print_next_page(next_cursor: Option<&str>) {
+    if let Some(cursor) = next_cursor {
+        println!("  More results: --cursor {cursor}\n");
+    }
+}
+
 /// Format transcript search results as a table.
-pub fn print_transcript_results(results: &[TranscriptResult], query: &str) {
+pub fn print_transcript_results(page: &Page<TranscriptResult>, query: &str, next_cursor: Option<&str>) {
+    let results = &page.results;
     if results.is_empty() {
-        println!("No results for \"{query}\"");
+        println!("No results for \"{query}\"{}", past_end(page));
         return;
     }
 
     println!(
-        "{} result{} for \"{}\":\n",
-        results.len(),
-        if results.len() == 1 { "" } else { "s" },
-        query
+        "{} result{} for \"{}\"{}:\n",
+        page.total,
+        if page.total == 1 { "" } else { "s" },
+        query,
+        shown_range(page)
     );
 
     // Header
@@ -58,6 +129,7 @@ pub fn print_transcript_results(results: &[TranscriptResult], query: &str) {
     );
     println!("  {}", "-".repeat(76));
 
+    let color = use_color();
     for r in results {
         let date_short = r.date.get(..10).unwrap_or(&r.date);
         println!(
@@ -71,47 +143,73 @@ pub fn print_transcript_results(results: &[TranscriptResult], query: &str) {
         // Snippet
         if !r.snippet.is_empty() {
             let snippet = r.snippet.replace('\n', " ");
-            println!("  {}", truncate(&format!("  {snippet}"), 76));
+            println!("  {}", paint_snippet(&truncate(&format!("  {snippet}"), 76), color));
         }
 
         println!("  id: {}\n", r.id);
     }
+    print_next_page(next_cursor);
 }
 
 /// Format segment search results as a table.
-pub fn print_segment_results(results: &[SegmentResult], query: &str) {
+pub fn print_segment_results(page: &Page<SegmentResult>, query: &str, next_cursor: Option<&str>) {
+    let results = &page.results;
     if results.is_empty() {
-        println!("No segment results for \"{query}\"");
+        println!("No segment results for \"{query}\"{}", past_end(page));
         return;
     }
 
     println!(
-        "{} segment{} for \"{}\":\n",
-        results.len(),
-        if results.len() == 1 { "" } else { "s" },
-        query
+        "{} segment{} for \"{}\"{}:\n",
+        page.total,
+        if page.total == 1 { "" } else { "s" },
+        query,
+        shown_range(page)
     );
 
+    let color = use_color();
     for r in results {
+        // Context lines are indented past the hit and dimmed on a terminal
+        let print_context = |c: &ContextSegment| {
+            let time = format_timestamp(c.start_time);
+            let line = truncate(&format!("[{time}] {}: {}", c.speaker, c.text.replace('\n', " ")), 78);
+            if color {
+                println!("    {DIM}{line}{RESET}");
+            } else {
+                println!("    {line}");
+            }
+        };
+        let (before, after): (Vec<_>, Vec<_>) =
+            r.context.iter().partition(|c| c.segment_index < r.segment_index);
+
+        before.into_iter().for_each(print_context);
         let time = format_timestamp(r.start_time);
-        let text = truncate(&r.text.replace('\n', " "), 80);
+        let text = paint_matches(&r.text.replace('\n', " "), &r.matches, 80, color);
         println!("  [{time}] {}: {text}", r.speaker);
+        after.into_iter().for_each(print_context);
         println!(
             "  └─ {} ({})\n",
             truncate(&r.transcript_title, 50),
             r.transcript_id
         );
     }
+    print_next_page(next_cursor);
 }
 
 /// Format transcript list as a table.
-pub fn print_transcript_list(results: &[TranscriptResult]) {
+pub fn print_transcript_list(page: &Page<TranscriptResult>, next_cursor: Option<&str>) {
+    let results = &page.results;
     if results.is_empty() {
-        println!("No transcripts found.");
+        println!("No transcripts found{}.", past_end(page));
         return;
     }
 
-    println!("{} transcript{}:\n", results.len(), if results.len() == 1 { "" } else { "s" });
+    println!(
+        "{} transcript{}{}:\n",
+        page.total,
+        if page.total == 1 { "" } else { "s" },
+        shown_range(page)
+    );
 
     println!(
         "  {:<42} {:<12} {:<10} {:<8}",
@@ -130,6 +228,7 @@ pub fn print_transcript_list(results: &[TranscriptResult]) {
         );
         println!("  id: {}\n", r.id);
     }
+    print_next_page(next_cursor);
 }
 
 /// Format a single transcript's details for `tss show`.
@@ -166,36 +265,74 @@ pub fn print_transcript_detail(t: &Transcript, speakers: &[String], tags: &[Stri
     }
 }
 
-/// Format segments for `tss expand`.
-pub fn print_segments(segments: &[Segment], speaker_filter: Option<&str>) {
+/// Format segments for `tss expand`. Segments matching `query` are marked
+/// with `>>`, and `...` stands for segments left out by `--context`.
+pub fn print_segments(segments: &[ExpandedSegment], query: Option<&str>) {
     if segments.is_empty() {
-        println!("No segments found.");
+        match query {
+            Some(q) => println!("No segments match \"{q}\""),
+            None => println!("No segments found."),
+        }
         return;
     }
 
-    let filtered: Vec<&Segment> = if let Some(speaker) = speaker_filter {
-        let lower = speaker.to_lowercase();
-        segments
-            .iter()
-            .filter(|s| s.speaker.to_lowercase().contains(&lower))
-            .collect()
-    } else {
-        segments.iter().collect()
-    };
-
-    println!("{} segment{}:\n", filtered.len(), if filtered.len() == 1 { "" } else { "s" });
+    match query {
+        Some(q) => {
+            let matched = segments.iter().filter(|s| s.matched == Some(true)).count();
+            println!(
+                "{} segment{}, {matched} matching \"{q}\":\n",
+                segments.len(),
+                if segments.len() == 1 { "" } else { "s" }
+            );
+        }
+        None => println!("{} segment{}:\n", segments.len(), if segments.len() == 1 { "" } else { "s" }),
+    }
 
     let mut last_speaker = String::new();
-    for seg in &filtered {
+    for (i, expanded) in segments.iter().enumerate() {
+        let seg = &expanded.segment;
+        if expanded.gap_before {
+            if i > 0 {
+                println!();
+            }
+            println!("  ...\n");
+        }
         let time = format_timestamp(seg.start_time);
-        if seg.speaker != last_speaker {
-            if !last_speaker.is_empty() {
+        if expanded.gap_before || seg.speaker != last_speaker {
+            if !last_speaker.is_empty() && !expanded.gap_before {
                 println!();
             }
             println!("  {} [{time}]:", seg.speaker);
             last_speaker = seg.speaker.clone();
         }
-        println!("    {}", seg.text);
+        let marker = if expanded.matched == Some(true) { " >> " } else { "    " };
+        println!("{marker}{}", seg.text);
+    }
+    println!();
+}
+
+/// Format per-file ingest outcomes for `tss ingest --keep-going`.
+pub fn print_ingest_report(files: &[FileReport]) {
+    if files.is_empty() {
+        println!("No files processed.");
+        return;
+    }
+
+    println!("  {:<10} {:<40} {:<26}", "STATUS", "FILE", "DETAIL");
+    println!("  {}", "-".repeat(76));
+
+    for f in files {
+        let detail = f
+            .error
+            .as_deref()
+            .or(f.title.as_deref())
+            .unwrap_or_default();
+        println!(
+            "  {:<10} {:<40} {}",
+            f.status.as_str(),
+            truncate(&f.path, 40),
+            truncate(&detail.replace('\n', " "), 60),
+        );
     }
     println!();
 }
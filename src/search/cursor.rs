@@ -0,0 +1,42 @@
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+/// Opaque page tokens for `--cursor`. A token records the offset of the next
+/// page and a fingerprint of the query it came from, so a token can't be
+/// replayed against a different search.
+///
+/// `scope` is everything that determines the result order: command, query,
+/// filters and sort.
+pub fn encode(offset: usize, scope: &str) -> String {
+    hex(format!("{offset}:{}", fingerprint(scope)).as_bytes())
+}
+
+/// Recover the offset from a token produced by `encode` with the same scope.
+pub fn decode(token: &str, scope: &str) -> Result<usize> {
+    let raw = unhex(token.trim())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .context("Invalid cursor")?;
+    let (offset, print) = raw.split_once(':').context("Invalid cursor")?;
+    if print != fingerprint(scope) {
+        bail!("Cursor belongs to a different query or filters; start again without --cursor");
+    }
+    offset.parse().context("Invalid cursor")
+}
+
+fn fingerprint(scope: &str) -> String {
+    hex(&Sha256::digest(scope.as_bytes())[..6])
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -1,11 +1,19 @@
-/// Filters that can be applied to search/list queries.
+use crate::db::dates;
+
+/// Filters that can be applied to search/list queries. Several values for
+/// one field match any of them; the `exclude_*` lists drop transcripts that
+/// match any of theirs.
 #[derive(Debug, Clone, Default)]
 pub struct Filters {
-    pub speaker: Option<String>,
-    pub source: Option<String>,
+    /// Speaker names (partial match)
+    pub speakers: Vec<String>,
+    pub sources: Vec<String>,
     pub from_date: Option<String>,
     pub to_date: Option<String>,
-    pub tag: Option<String>,
+    pub tags: Vec<String>,
+    pub exclude_speakers: Vec<String>,
+    pub exclude_sources: Vec<String>,
+    pub exclude_tags: Vec<String>,
 }
 
 impl Filters {
@@ -15,37 +23,73 @@ impl Filters {
         let mut conditions: Vec<String> = Vec::new();
         let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
 
-        if let Some(ref source) = self.source {
-            conditions.push(format!("t.source = ?{}", params.len() + 1));
-            params.push(Box::new(source.clone()));
+        if !self.sources.is_empty() {
+            let list = placeholders(&mut params, self.sources.iter().cloned());
+            conditions.push(format!("t.source IN ({list})"));
+        }
+        if !self.exclude_sources.is_empty() {
+            let list = placeholders(&mut params, self.exclude_sources.iter().cloned());
+            conditions.push(format!("t.source NOT IN ({list})"));
         }
 
         if let Some(ref from) = self.from_date {
             conditions.push(format!("t.date >= ?{}", params.len() + 1));
-            params.push(Box::new(from.clone()));
+            params.push(Box::new(dates::filter_bound(from, false)));
         }
 
         if let Some(ref to) = self.to_date {
-            conditions.push(format!("t.date <= ?{}", params.len() + 1));
-            params.push(Box::new(to.clone()));
+            conditions.push(format!("t.date <> '' AND t.date <= ?{}", params.len() + 1));
+            params.push(Box::new(dates::filter_bound(to, true)));
         }
 
-        if let Some(ref speaker) = self.speaker {
+        if !self.speakers.is_empty() {
+            let matches = speaker_matches(&mut params, &self.speakers);
             conditions.push(format!(
-                "t.id IN (SELECT transcript_id FROM speakers WHERE name LIKE ?{})",
-                params.len() + 1
+                "t.id IN (SELECT transcript_id FROM speakers WHERE {matches})"
+            ));
+        }
+        if !self.exclude_speakers.is_empty() {
+            let matches = speaker_matches(&mut params, &self.exclude_speakers);
+            conditions.push(format!(
+                "t.id NOT IN (SELECT transcript_id FROM speakers WHERE {matches})"
             ));
-            params.push(Box::new(format!("%{speaker}%")));
         }
 
-        if let Some(ref tag) = self.tag {
+        if !self.tags.is_empty() {
+            let list = placeholders(&mut params, self.tags.iter().cloned());
             conditions.push(format!(
-                "t.id IN (SELECT transcript_id FROM tags WHERE tag = ?{})",
-                params.len() + 1
+                "t.id IN (SELECT transcript_id FROM tags WHERE tag IN ({list}))"
+            ));
+        }
+        if !self.exclude_tags.is_empty() {
+            let list = placeholders(&mut params, self.exclude_tags.iter().cloned());
+            conditions.push(format!(
+                "t.id NOT IN (SELECT transcript_id FROM tags WHERE tag IN ({list}))"
             ));
-            params.push(Box::new(tag.clone()));
         }
 
         (conditions, params)
     }
 }
+
+/// Push `values` as params and return their comma-separated placeholders.
+fn placeholders(
+    params: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    values: impl Iterator<Item = String>,
+) -> String {
+    let mut list = Vec::new();
+    for value in values {
+        params.push(Box::new(value));
+        list.push(format!("?{}", params.len()));
+    }
+    list.join(", ")
+}
+
+/// `name LIKE ?a OR name LIKE ?b ...` for partial speaker matches.
+fn speaker_matches(params: &mut Vec<Box<dyn rusqlite::types::ToSql>>, speakers: &[String]) -> String {
+    let list = placeholders(params, speakers.iter().map(|s| format!("%{s}%")));
+    list.split(", ")
+        .map(|p| format!("name LIKE {p}"))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
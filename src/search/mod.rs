@@ -1,8 +1,12 @@
+pub mod cursor;
 pub mod filters;
+pub mod query;
 
 use anyhow::Result;
 use serde::Serialize;
+use std::collections::HashSet;
 
+use crate::db::models::Segment;
 use crate::db::Database;
 use filters::Filters;
 
@@ -26,7 +30,66 @@ pub struct SegmentResult {
     pub text: String,
     pub start_time: f64,
     pub end_time: f64,
+    pub segment_index: i64,
     pub rank: f64,
+    /// `text` with matches wrapped in `>>>` / `<<<`, as in transcript snippets
+    pub highlight: String,
+    /// Byte ranges of the matches within `text`
+    pub matches: Vec<MatchRange>,
+    /// Neighbouring segments, in transcript order, with `--context`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<ContextSegment>,
+}
+
+/// A matched term in a segment's text, as a half-open byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Markers `highlight()` puts around matches; control characters, so they
+/// can't collide with transcript text.
+const MATCH_OPEN: char = '\u{1}';
+const MATCH_CLOSE: char = '\u{2}';
+
+/// Strip `highlight()` markers, returning the plain text and match ranges.
+fn split_highlight(marked: &str) -> (String, Vec<MatchRange>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for ch in marked.chars() {
+        match ch {
+            MATCH_OPEN => start = Some(text.len()),
+            MATCH_CLOSE => {
+                if let Some(start) = start.take() {
+                    ranges.push(MatchRange { start, end: text.len() });
+                }
+            }
+            _ => text.push(ch),
+        }
+    }
+    (text, ranges)
+}
+
+/// A segment shown around a segment search hit.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextSegment {
+    pub segment_id: i64,
+    pub speaker: String,
+    pub text: String,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub segment_index: i64,
+}
+
+/// How much of the transcript to attach around each segment hit.
+#[derive(Debug, Clone, Copy)]
+pub enum ContextWindow {
+    /// This many segments on each side, by `segment_index`
+    Segments(usize),
+    /// Segments overlapping this many seconds before and after the hit
+    Seconds(f64),
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -39,26 +102,104 @@ pub struct SearchOutput {
     pub segments: Vec<SegmentResult>,
 }
 
+/// One page of search or list results, with the number of matches across
+/// all pages.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub total: usize,
+    pub offset: usize,
+    pub results: Vec<T>,
+}
+
+impl<T> Page<T> {
+    /// Offset of the following page, if there is one.
+    pub fn next_offset(&self) -> Option<usize> {
+        let end = self.offset + self.results.len();
+        (!self.results.is_empty() && end < self.total).then_some(end)
+    }
+}
+
+/// A segment as shown by `tss expand`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpandedSegment {
+    #[serde(flatten)]
+    pub segment: Segment,
+    /// Whether the segment matches `--query`; absent without one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched: Option<bool>,
+    /// Segments were left out between this one and the previous
+    #[serde(skip)]
+    pub gap_before: bool,
+}
+
+/// Pick the segments `tss expand` shows. `matches` flags segments hit by the
+/// query; with `context`, only those plus `context` neighbours on each side
+/// are kept.
+pub fn expand_segments(
+    segments: Vec<Segment>,
+    matches: Option<&HashSet<i64>>,
+    context: Option<usize>,
+) -> Vec<ExpandedSegment> {
+    let matched: Vec<bool> = segments
+        .iter()
+        .map(|s| matches.is_some_and(|m| m.contains(&s.id)))
+        .collect();
+    let keep: Vec<bool> = match context {
+        Some(n) => (0..segments.len())
+            .map(|i| {
+                let lo = i.saturating_sub(n);
+                let hi = (i + n).min(segments.len() - 1);
+                matched[lo..=hi].contains(&true)
+            })
+            .collect(),
+        None => vec![true; segments.len()],
+    };
+
+    let mut expanded = Vec::new();
+    let mut skipped = false;
+    for (i, segment) in segments.into_iter().enumerate() {
+        if !keep[i] {
+            skipped = true;
+            continue;
+        }
+        expanded.push(ExpandedSegment {
+            segment,
+            matched: matches.map(|_| matched[i]),
+            gap_before: skipped,
+        });
+        skipped = false;
+    }
+    expanded
+}
+
 impl Database {
+    /// IDs of the segments in one transcript that match an FTS5 query.
+    pub fn matching_segment_ids(&self, transcript_id: &str, query: &str) -> Result<HashSet<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.id
+             FROM segments_fts
+             JOIN segments s ON s.rowid = segments_fts.rowid
+             WHERE segments_fts MATCH ?1 AND s.transcript_id = ?2",
+        )?;
+        let ids = stmt
+            .query_map(rusqlite::params![query, transcript_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(ids)
+    }
+
     /// Search transcripts using FTS5. Returns transcript-level results with BM25 ranking.
     pub fn search_transcripts(
         &self,
         query: &str,
         filters: &Filters,
         limit: usize,
-    ) -> Result<Vec<TranscriptResult>> {
+        offset: usize,
+    ) -> Result<Page<TranscriptResult>> {
         let (filter_conditions, filter_params) = filters.transcript_conditions();
 
-        let mut where_parts = vec!["transcripts_fts MATCH ?1".to_string()];
-        // Offset filter param indices by 1 (query is ?1)
-        for (i, cond) in filter_conditions.iter().enumerate() {
-            // Replace ?N with ?(N+1) since ?1 is the query
-            let adjusted = cond.replace(
-                &format!("?{}", i + 1),
-                &format!("?{}", i + 2),
-            );
-            where_parts.push(adjusted);
-        }
+        // Filters take ?1..?N, then the query, limit and offset
+        let mut where_parts = filter_conditions;
+        where_parts.push(format!("transcripts_fts MATCH ?{}", filter_params.len() + 1));
 
         let where_clause = where_parts.join(" AND ");
 
@@ -69,20 +210,31 @@ impl Database {
              FROM transcripts_fts
              JOIN transcripts t ON t.rowid = transcripts_fts.rowid
              WHERE {where_clause}
-             ORDER BY rank
-             LIMIT ?{}",
-            filter_params.len() + 2
+             ORDER BY rank, t.id
+             LIMIT ?{} OFFSET ?{}",
+            filter_params.len() + 2,
+            filter_params.len() + 3
         );
 
         let mut stmt = self.conn.prepare(&sql)?;
 
-        // Build params: query, filter_params..., limit
+        // Build params: filter_params..., query, limit, offset
         let mut all_params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-        all_params.push(Box::new(query.to_string()));
         for p in filter_params {
             all_params.push(p);
         }
+        all_params.push(Box::new(query.to_string()));
+        let total = count(
+            &self.conn,
+            &format!(
+                "SELECT COUNT(*) FROM transcripts_fts
+                 JOIN transcripts t ON t.rowid = transcripts_fts.rowid
+                 WHERE {where_clause}"
+            ),
+            &all_params,
+        )?;
         all_params.push(Box::new(limit as i64));
+        all_params.push(Box::new(offset as i64));
 
         let param_refs: Vec<&dyn rusqlite::types::ToSql> =
             all_params.iter().map(|p| p.as_ref()).collect();
@@ -103,7 +255,11 @@ impl Database {
         for row in rows {
             results.push(row?);
         }
-        Ok(results)
+        Ok(Page {
+            total,
+            offset,
+            results,
+        })
     }
 
     /// Search segments using FTS5. Returns segment-level results with transcript context.
@@ -112,59 +268,70 @@ impl Database {
         query: &str,
         filters: &Filters,
         limit: usize,
-    ) -> Result<Vec<SegmentResult>> {
+        offset: usize,
+    ) -> Result<Page<SegmentResult>> {
         let (filter_conditions, filter_params) = filters.transcript_conditions();
 
-        let mut where_parts = vec!["segments_fts MATCH ?1".to_string()];
-        for (i, cond) in filter_conditions.iter().enumerate() {
-            let adjusted = cond
-                .replace(
-                    &format!("?{}", i + 1),
-                    &format!("?{}", i + 2),
-                )
-                .replace("t.id", "t.id")
-                .replace("t.source", "t.source")
-                .replace("t.date", "t.date");
-            where_parts.push(adjusted);
-        }
+        // Filters take ?1..?N, then the query, limit and offset
+        let mut where_parts = filter_conditions;
+        where_parts.push(format!("segments_fts MATCH ?{}", filter_params.len() + 1));
 
         let where_clause = where_parts.join(" AND ");
 
         let sql = format!(
-            "SELECT s.transcript_id, t.title, s.id, s.speaker, s.text,
-                    s.start_time, s.end_time,
+            "SELECT s.transcript_id, t.title, s.id, s.speaker,
+                    highlight(segments_fts, 0, char(1), char(2)),
+                    s.start_time, s.end_time, s.segment_index,
                     bm25(segments_fts, 2.0, 1.0) AS rank
              FROM segments_fts
              JOIN segments s ON s.rowid = segments_fts.rowid
              JOIN transcripts t ON t.id = s.transcript_id
              WHERE {where_clause}
-             ORDER BY rank
-             LIMIT ?{}",
-            filter_params.len() + 2
+             ORDER BY rank, s.id
+             LIMIT ?{} OFFSET ?{}",
+            filter_params.len() + 2,
+            filter_params.len() + 3
         );
 
         let mut stmt = self.conn.prepare(&sql)?;
 
         let mut all_params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-        all_params.push(Box::new(query.to_string()));
         for p in filter_params {
             all_params.push(p);
         }
+        all_params.push(Box::new(query.to_string()));
+        let total = count(
+            &self.conn,
+            &format!(
+                "SELECT COUNT(*) FROM segments_fts
+                 JOIN segments s ON s.rowid = segments_fts.rowid
+                 JOIN transcripts t ON t.id = s.transcript_id
+                 WHERE {where_clause}"
+            ),
+            &all_params,
+        )?;
         all_params.push(Box::new(limit as i64));
+        all_params.push(Box::new(offset as i64));
 
         let param_refs: Vec<&dyn rusqlite::types::ToSql> =
             all_params.iter().map(|p| p.as_ref()).collect();
 
         let rows = stmt.query_map(param_refs.as_slice(), |row| {
+            let marked: String = row.get(4)?;
+            let (text, matches) = split_highlight(&marked);
             Ok(SegmentResult {
                 transcript_id: row.get(0)?,
                 transcript_title: row.get(1)?,
                 segment_id: row.get(2)?,
                 speaker: row.get(3)?,
-                text: row.get(4)?,
+                highlight: marked.replace(MATCH_OPEN, ">>>").replace(MATCH_CLOSE, "<<<"),
+                matches,
+                text,
                 start_time: row.get(5)?,
                 end_time: row.get(6)?,
-                rank: row.get(7)?,
+                segment_index: row.get(7)?,
+                rank: row.get(8)?,
+                context: Vec::new(),
             })
         })?;
 
@@ -172,7 +339,47 @@ impl Database {
         for row in rows {
             results.push(row?);
         }
-        Ok(results)
+        Ok(Page {
+            total,
+            offset,
+            results,
+        })
+    }
+
+    /// Fill in `context` on each segment hit with its neighbours.
+    pub fn attach_context(&self, results: &mut [SegmentResult], window: ContextWindow) -> Result<()> {
+        let sql = match window {
+            ContextWindow::Segments(_) => {
+                "SELECT id, speaker, text, start_time, end_time, segment_index
+                 FROM segments
+                 WHERE transcript_id = ?1 AND id <> ?2
+                   AND segment_index BETWEEN ?3 - ?5 AND ?4 + ?5
+                 ORDER BY segment_index"
+            }
+            ContextWindow::Seconds(_) => {
+                "SELECT id, speaker, text, start_time, end_time, segment_index
+                 FROM segments
+                 WHERE transcript_id = ?1 AND id <> ?2
+                   AND end_time >= ?3 - ?5 AND start_time <= ?4 + ?5
+                 ORDER BY segment_index"
+            }
+        };
+        let mut stmt = self.conn.prepare(sql)?;
+
+        for r in results.iter_mut() {
+            let rows = match window {
+                ContextWindow::Segments(n) => stmt.query_map(
+                    rusqlite::params![r.transcript_id, r.segment_id, r.segment_index, r.segment_index, n as i64],
+                    context_row,
+                )?,
+                ContextWindow::Seconds(secs) => stmt.query_map(
+                    rusqlite::params![r.transcript_id, r.segment_id, r.start_time, r.end_time, secs],
+                    context_row,
+                )?,
+            };
+            r.context = rows.collect::<rusqlite::Result<_>>()?;
+        }
+        Ok(())
     }
 
     /// List transcripts with optional filters and sorting.
@@ -181,7 +388,8 @@ impl Database {
         filters: &Filters,
         sort: &str,
         limit: usize,
-    ) -> Result<Vec<TranscriptResult>> {
+        offset: usize,
+    ) -> Result<Page<TranscriptResult>> {
         let (filter_conditions, filter_params) = filters.transcript_conditions();
 
         let where_clause = if filter_conditions.is_empty() {
@@ -190,9 +398,10 @@ impl Database {
             format!("WHERE {}", filter_conditions.join(" AND "))
         };
 
+        // Ties are broken by ID so pages don't shift between calls
         let order_by = match sort {
-            "title" => "t.title ASC",
-            _ => "t.date DESC",
+            "title" => "t.title ASC, t.id ASC",
+            _ => "t.date DESC, t.id ASC",
         };
 
         let sql = format!(
@@ -200,8 +409,9 @@ impl Database {
              FROM transcripts t
              {where_clause}
              ORDER BY {order_by}
-             LIMIT ?{}",
-            filter_params.len() + 1
+             LIMIT ?{} OFFSET ?{}",
+            filter_params.len() + 1,
+            filter_params.len() + 2
         );
 
         let mut stmt = self.conn.prepare(&sql)?;
@@ -210,7 +420,13 @@ impl Database {
         for p in filter_params {
             all_params.push(p);
         }
+        let total = count(
+            &self.conn,
+            &format!("SELECT COUNT(*) FROM transcripts t {where_clause}"),
+            &all_params,
+        )?;
         all_params.push(Box::new(limit as i64));
+        all_params.push(Box::new(offset as i64));
 
         let param_refs: Vec<&dyn rusqlite::types::ToSql> =
             all_params.iter().map(|p| p.as_ref()).collect();
@@ -231,6 +447,28 @@ impl Database {
         for row in rows {
             results.push(row?);
         }
-        Ok(results)
+        Ok(Page {
+            total,
+            offset,
+            results,
+        })
     }
 }
+
+/// Run a `SELECT COUNT(*)` query.
+fn count(conn: &rusqlite::Connection, sql: &str, params: &[Box<dyn rusqlite::types::ToSql>]) -> Result<usize> {
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let n: i64 = conn.query_row(sql, param_refs.as_slice(), |row| row.get(0))?;
+    Ok(n as usize)
+}
+
+fn context_row(row: &rusqlite::Row) -> rusqlite::Result<ContextSegment> {
+    Ok(ContextSegment {
+        segment_id: row.get(0)?,
+        speaker: row.get(1)?,
+        text: row.get(2)?,
+        start_time: row.get(3)?,
+        end_time: row.get(4)?,
+        segment_index: row.get(5)?,
+    })
+}
@@ -0,0 +1,174 @@
+use anyhow::{bail, Result};
+
+use super::filters::Filters;
+use crate::db::dates;
+
+/// FTS5 operators a field filter can't sit next to, since it isn't part of
+/// the MATCH expression.
+const OPERATORS: &[&str] = &["AND", "OR", "NOT"];
+
+const OPERATOR_ERROR: &str =
+    "Field filters can't be combined with AND/OR/NOT; repeat a field (speaker:a speaker:b) to match either value";
+
+/// What a search matches against, which decides who handles `speaker:`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    Transcripts,
+    Segments,
+}
+
+/// A search string split into its FTS5 MATCH expression and inline filters.
+#[derive(Debug, Clone)]
+pub struct InlineQuery {
+    /// What's left for FTS5; empty if the query was nothing but filters
+    pub text: String,
+    pub filters: Filters,
+}
+
+/// Pull `field:value` filters out of a search string and add them to
+/// `filters` (usually the ones from command-line flags):
+///
+/// - `speaker:`, `source:`, `tag:` — repeat a field to match any of the
+///   values; prefix with `-` to exclude (`-tag:internal`)
+/// - `after:` / `from:` and `before:` / `to:` — date bounds, inclusive like
+///   `--from` / `--to`. Where they overlap with the flags or each other, the
+///   narrower range wins.
+///
+/// In segment search `speaker:` is left to FTS5, where it is the column
+/// filter on `segments_fts` and matches only what that speaker said;
+/// `-speaker:` becomes a trailing `NOT speaker:...`.
+///
+/// Values with spaces are quoted: `speaker:"Ana Lima"`. Anything else, FTS5
+/// column filters like `title:roadmap` included, stays in the MATCH
+/// expression, and so does everything inside parentheses.
+pub fn parse(input: &str, mut filters: Filters, mode: SearchMode) -> Result<InlineQuery> {
+    let tokens = tokenize(input);
+    let mut kept: Vec<&str> = Vec::new();
+    let mut not_speakers: Vec<String> = Vec::new();
+    let mut removed_last = false;
+
+    for token in &tokens {
+        let filter = field_filter(token).filter(|(negated, field, _)| {
+            *negated || field != "speaker" || mode == SearchMode::Transcripts
+        });
+        match filter {
+            Some((negated, field, value)) => {
+                if value.is_empty() {
+                    bail!("Missing value for {field}: in query");
+                }
+                if field == "speaker" && mode == SearchMode::Segments {
+                    not_speakers.push(format!("speaker:\"{value}\""));
+                } else {
+                    apply(&mut filters, negated, &field, value)?;
+                }
+                if kept.last().is_some_and(|t| is_operator(t)) {
+                    bail!(OPERATOR_ERROR);
+                }
+                removed_last = true;
+            }
+            None => {
+                if removed_last && is_operator(token) {
+                    bail!(OPERATOR_ERROR);
+                }
+                kept.push(token);
+                removed_last = false;
+            }
+        }
+    }
+
+    let mut text = kept.join(" ");
+    if !text.is_empty() && !not_speakers.is_empty() {
+        text = format!("({text}) NOT {}", not_speakers.join(" NOT "));
+    }
+    Ok(InlineQuery { text, filters })
+}
+
+fn is_operator(token: &str) -> bool {
+    OPERATORS.contains(&token)
+}
+
+/// Split on whitespace outside double quotes and parentheses, keeping
+/// tokens verbatim.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut depth = 0usize;
+
+    for ch in input.chars() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && !in_quotes && depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// `[-]field:value` for the fields handled here, with quotes stripped from
+/// the value.
+fn field_filter(token: &str) -> Option<(bool, String, &str)> {
+    let (negated, rest) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+    let (field, value) = rest.split_once(':')?;
+    let field = field.to_lowercase();
+    if !matches!(
+        field.as_str(),
+        "speaker" | "source" | "tag" | "after" | "from" | "before" | "to"
+    ) {
+        return None;
+    }
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    Some((negated, field, value))
+}
+
+fn apply(filters: &mut Filters, negated: bool, field: &str, value: &str) -> Result<()> {
+    let value = value.to_string();
+    match (field, negated) {
+        ("speaker", false) => filters.speakers.push(value),
+        ("speaker", true) => filters.exclude_speakers.push(value),
+        ("source", false) => filters.sources.push(value),
+        ("source", true) => filters.exclude_sources.push(value),
+        ("tag", false) => filters.tags.push(value),
+        ("tag", true) => filters.exclude_tags.push(value),
+        ("after" | "from", false) => {
+            filters.from_date = Some(narrower(filters.from_date.take(), value, false));
+        }
+        ("before" | "to", false) => {
+            filters.to_date = Some(narrower(filters.to_date.take(), value, true));
+        }
+        (field, _) => bail!("{field}: can't be negated"),
+    }
+    Ok(())
+}
+
+/// Of an existing date bound and a new one, the one that admits less: the
+/// later start or the earlier end.
+fn narrower(current: Option<String>, value: String, end: bool) -> String {
+    let Some(current) = current else {
+        return value;
+    };
+    let (old, new) = (dates::filter_bound(&current, end), dates::filter_bound(&value, end));
+    let take_new = if end { new < old } else { new > old };
+    if take_new {
+        value
+    } else {
+        current
+    }
+}
@@ -219,6 +219,7 @@ impl FirefliesTranscript {
                 start_time: s.start_time.unwrap_or(0.0),
                 end_time: s.end_time.unwrap_or(0.0),
                 segment_index: i as i64,
+                metadata: None,
             });
         }
 
@@ -303,6 +304,7 @@ impl FirefliesTranscript {
             summary,
             raw_text,
             metadata,
+            content_hash: None,
             speakers,
             segments,
             tags: Vec::new(),
@@ -73,6 +73,47 @@ pub struct SyncOptions {
     pub dry_run: bool,
 }
 
+/// Fetched transcripts written per transaction during a sync.
+const INSERT_BATCH: usize = 25;
+
+/// Transcripts fetched but not yet written, with their 1-based position in
+/// the sync for error output.
+#[derive(Default)]
+struct Pending<'a> {
+    entries: Vec<(usize, &'a RemoteTranscript)>,
+    transcripts: Vec<NewTranscript>,
+}
+
+impl Pending<'_> {
+    /// Write everything pending in one transaction, counting each transcript
+    /// as synced or failed. Progress was printed as each one was fetched, so
+    /// only failures are reported here, through `report_failure`.
+    fn insert(
+        &mut self,
+        db: &Database,
+        synced: &mut usize,
+        failed: &mut usize,
+        report_failure: impl Fn(usize, &RemoteTranscript, &anyhow::Error),
+    ) -> Result<()> {
+        if self.transcripts.is_empty() {
+            return Ok(());
+        }
+        let results = db.insert_many(&self.transcripts)?;
+        for ((n, rt), result) in self.entries.iter().zip(results) {
+            match result {
+                Ok(()) => *synced += 1,
+                Err(e) => {
+                    *failed += 1;
+                    report_failure(*n, rt, &e);
+                }
+            }
+        }
+        self.entries.clear();
+        self.transcripts.clear();
+        Ok(())
+    }
+}
+
 /// Run an initial or incremental sync.
 pub fn run_sync(
     connector: &dyn TranscriptConnector,
@@ -198,34 +239,28 @@ pub fn run_sync(
     let mut failed = 0usize;
     let width = format!("{}", new_count).len();
 
+    // Progress is printed as each transcript is fetched; they are written
+    // INSERT_BATCH at a time
+    let insert_failed = |n: usize, rt: &RemoteTranscript, e: &anyhow::Error| {
+        eprintln!("  [{:>width$}/{}] FAILED to insert {}: {}", n, new_count, rt.title, e);
+    };
+    let mut pending = Pending::default();
     for (i, rt) in new_transcripts.iter().enumerate() {
         match connector.fetch_one(&rt.id) {
             Ok(transcript) => {
-                let seg_count = transcript.segments.len();
-                let ai_count = transcript.action_items.len();
-                match db.insert_transcript(&transcript) {
-                    Ok(()) => {
-                        synced += 1;
-                        eprintln!(
-                            "  [{:>width$}/{}] {} ({}) — {} segments, {} action items",
-                            i + 1,
-                            new_count,
-                            rt.title,
-                            &rt.date[..10.min(rt.date.len())],
-                            seg_count,
-                            ai_count,
-                        );
-                    }
-                    Err(e) => {
-                        failed += 1;
-                        eprintln!(
-                            "  [{:>width$}/{}] FAILED to insert {}: {}",
-                            i + 1,
-                            new_count,
-                            rt.title,
-                            e,
-                        );
-                    }
+                eprintln!(
+                    "  [{:>width$}/{}] {} ({}) — {} segments, {} action items",
+                    i + 1,
+                    new_count,
+                    rt.title,
+                    &rt.date[..10.min(rt.date.len())],
+                    transcript.segments.len(),
+                    transcript.action_items.len(),
+                );
+                pending.entries.push((i + 1, rt));
+                pending.transcripts.push(transcript);
+                if pending.transcripts.len() >= INSERT_BATCH {
+                    pending.insert(db, &mut synced, &mut failed, insert_failed)?;
                 }
             }
             Err(e) => {
@@ -240,6 +275,7 @@ pub fn run_sync(
             }
         }
     }
+    pending.insert(db, &mut synced, &mut failed, insert_failed)?;
 
     let status = if failed > 0 && synced == 0 {
         "failed"
@@ -382,24 +418,27 @@ pub fn run_audit(
             let total = missing_locally.len();
             let mut synced = 0usize;
             let mut errors = 0usize;
+            let insert_failed = |n: usize, _: &RemoteTranscript, e: &anyhow::Error| {
+                eprintln!("  [{}/{}] FAILED: {}", n, total, e);
+            };
+            let mut pending = Pending::default();
             for (i, rt) in missing_locally.iter().enumerate() {
                 match connector.fetch_one(&rt.id) {
-                    Ok(transcript) => match db.insert_transcript(&transcript) {
-                        Ok(()) => {
-                            synced += 1;
-                            eprintln!("  [{}/{}] {}", i + 1, total, rt.title);
+                    Ok(transcript) => {
+                        eprintln!("  [{}/{}] {}", i + 1, total, rt.title);
+                        pending.entries.push((i + 1, rt));
+                        pending.transcripts.push(transcript);
+                        if pending.transcripts.len() >= INSERT_BATCH {
+                            pending.insert(db, &mut synced, &mut errors, insert_failed)?;
                         }
-                        Err(e) => {
-                            errors += 1;
-                            eprintln!("  [{}/{}] FAILED: {}", i + 1, total, e);
-                        }
-                    },
+                    }
                     Err(e) => {
                         errors += 1;
                         eprintln!("  [{}/{}] FAILED to fetch: {}", i + 1, total, e);
                     }
                 }
             }
+            pending.insert(db, &mut synced, &mut errors, insert_failed)?;
             state::complete_sync_run(
                 &db.conn,
                 run_id,
@@ -240,6 +240,7 @@ impl PocketRecording {
                         start_time: s.start.unwrap_or(0.0),
                         end_time: s.end.unwrap_or(0.0),
                         segment_index: i as i64,
+                        metadata: None,
                     });
                 }
             }
@@ -321,6 +322,7 @@ impl PocketRecording {
             summary,
             raw_text,
             metadata: None,
+            content_hash: None,
             speakers,
             segments,
             tags,
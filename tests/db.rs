@@ -0,0 +1,101 @@
+use std::path::{Path, PathBuf};
+use tss::db::models::{NewSegment, NewTranscript};
+use tss::db::{schema, Database};
+
+fn transcript(id: &str, text: &str) -> NewTranscript {
+    NewTranscript {
+        id: id.to_string(),
+        title: format!("Meeting {id}"),
+        date: "2024-03-01".to_string(),
+        duration_seconds: 60.0,
+        source: "test".to_string(),
+        summary: String::new(),
+        raw_text: text.to_string(),
+        metadata: None,
+        content_hash: None,
+        speakers: vec!["Alice".to_string()],
+        segments: vec![NewSegment {
+            speaker: "Alice".to_string(),
+            text: text.to_string(),
+            start_time: 0.0,
+            end_time: 5.0,
+            segment_index: 0,
+            metadata: None,
+        }],
+        tags: Vec::new(),
+        keywords: Vec::new(),
+        action_items: Vec::new(),
+    }
+}
+
+/// A fresh database in the temp dir, removed by `remove`.
+fn temp_db(name: &str) -> (Database, PathBuf) {
+    let path = std::env::temp_dir().join(format!("tss-{name}-{}.db", std::process::id()));
+    remove(&path);
+    (Database::open(&path).unwrap(), path)
+}
+
+fn remove(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+    }
+}
+
+fn count(db: &Database, sql: &str) -> i64 {
+    db.conn.query_row(sql, [], |r| r.get(0)).unwrap()
+}
+
+#[test]
+fn insert_many_reports_taken_ids_instead_of_replacing() {
+    let (db, path) = temp_db("insert-many");
+
+    let results = db
+        .insert_many(&[
+            transcript("a", "first version"),
+            transcript("b", "another meeting"),
+            transcript("a", "second version"),
+        ])
+        .unwrap();
+    assert!(results[0].is_ok());
+    assert!(results[1].is_ok());
+    let err = results[2].as_ref().unwrap_err().to_string();
+    assert_eq!(err, "Transcript a already exists");
+
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM segments WHERE transcript_id = 'a'"), 1);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM segments_fts WHERE segments_fts MATCH 'version'"), 1);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM transcripts_fts WHERE transcripts_fts MATCH 'second'"), 0);
+
+    drop(db);
+    remove(&path);
+}
+
+#[test]
+fn batch_writes_index_each_segment_once() {
+    let (db, path) = temp_db("upsert-batch");
+
+    // The trigger must skip on the same key the batch writes set
+    let trigger: String = db
+        .conn
+        .query_row("SELECT sql FROM sqlite_master WHERE name = 'segments_ai'", [], |r| r.get(0))
+        .unwrap();
+    assert!(trigger.contains(&format!("'{}'", schema::DEFER_SEGMENT_FTS)));
+
+    let batch = [transcript("a", "alpha budget"), transcript("b", "beta budget")];
+    assert!(db.upsert_batch(&batch).unwrap().iter().all(|r| r.is_ok()));
+    // Replacing rewrites the segments through the same path
+    let batch = [transcript("a", "alpha budget revised"), transcript("c", "gamma budget")];
+    assert!(db.upsert_batch(&batch).unwrap().iter().all(|r| r.is_ok()));
+
+    let segments = count(&db, "SELECT COUNT(*) FROM segments");
+    assert_eq!(segments, 3);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM segments_fts_docsize"), segments);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM segments_fts WHERE segments_fts MATCH 'budget'"), segments);
+    db.conn
+        .execute("INSERT INTO segments_fts(segments_fts, rank) VALUES ('integrity-check', 1)", [])
+        .unwrap();
+    let flag = format!("SELECT COUNT(*) FROM tss_meta WHERE key = '{}'", schema::DEFER_SEGMENT_FTS);
+    assert_eq!(count(&db, &flag), 0);
+
+    drop(db);
+    remove(&path);
+}
@@ -0,0 +1,43 @@
+use tss::ingest::deepgram::parse_deepgram;
+
+#[test]
+fn multichannel_words_are_merged_in_time_order_by_channel() {
+    let value = serde_json::from_str(include_str!("fixtures/deepgram/two_channel.json")).unwrap();
+    let t = parse_deepgram(value, "call.json", None).unwrap();
+
+    let lines: Vec<(&str, f64, &str)> = t
+        .segments
+        .iter()
+        .map(|s| (s.speaker.as_str(), s.start_time, s.text.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            ("Channel 0", 0.1, "Hello caller."),
+            ("Channel 1", 1.0, "Hi, I need help."),
+            ("Channel 0", 3.0, "Sure thing."),
+        ]
+    );
+    assert_eq!(t.speakers, ["Channel 0", "Channel 1"]);
+}
+
+#[test]
+fn channels_keep_their_index_when_an_earlier_one_is_empty() {
+    let value = serde_json::json!({
+        "results": {
+            "channels": [
+                { "alternatives": [] },
+                { "alternatives": [{ "transcript": "Hi.", "words": [
+                    { "word": "hi", "punctuated_word": "Hi.", "start": 1.0, "end": 1.2 }
+                ] }] },
+                { "alternatives": [{ "transcript": "Hello.", "words": [
+                    { "word": "hello", "punctuated_word": "Hello.", "start": 2.0, "end": 2.4 }
+                ] }] }
+            ]
+        }
+    });
+    let t = parse_deepgram(value, "call.json", None).unwrap();
+
+    let speakers: Vec<&str> = t.segments.iter().map(|s| s.speaker.as_str()).collect();
+    assert_eq!(speakers, ["Channel 1", "Channel 2"]);
+}
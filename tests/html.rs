@@ -0,0 +1,22 @@
+use tss::ingest::html::parse_html;
+
+#[test]
+fn avatar_images_and_wrapper_classes_do_not_swallow_the_transcript() {
+    let page = include_str!("fixtures/html/avatar.html");
+    let t = parse_html(page, "avatar.html", None).unwrap();
+
+    assert_eq!(t.title, "Design Review");
+    let lines: Vec<(&str, f64, &str)> = t
+        .segments
+        .iter()
+        .filter(|s| !s.speaker.is_empty())
+        .map(|s| (s.speaker.as_str(), s.start_time, s.text.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            ("Alice Chen", 5.0, "Let's go through the new onboarding flow."),
+            ("Bob Stone", 12.0, "The second screen still needs a skip button."),
+        ]
+    );
+}